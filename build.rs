@@ -1,20 +1,41 @@
 //! Compiles the Objective-C helpers in `src/native/` into the cargo build and
-//! links the macOS frameworks they use. All OS interaction lives in those `.m`
-//! files; Rust calls them through plain C functions (see rust-objc.md).
+//! links the macOS frameworks they use. Off macOS, `fallback.c` is compiled
+//! instead: no-op stand-ins for the same functions, plus notify-send for
+//! notifications. Either way the native side lives in `src/native/`, and Rust
+//! calls it through plain C functions (see rust-objc.md).
 
 fn main() {
     println!("cargo:rerun-if-changed=src/native");
+    // elsewhere, no-op stand-ins (plus notify-send) keep the core running
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("macos") {
+        cc::Build::new().file("src/native/fallback.c").compile("native");
+        return;
+    }
     cc::Build::new()
         .file("src/native/notifications.m")
         .file("src/native/camera.m")
         .file("src/native/tray.m")
+        .file("src/native/network.m")
+        .file("src/native/focus.m")
+        .file("src/native/mic.m")
+        .file("src/native/power.m")
         .flag("-fobjc-arc")
         .compile("native");
 
     // rustc drives the final link, so it won't add these automatically the way
     // clang does when it links .m files itself.
     println!("cargo:rustc-link-lib=objc");
-    for framework in ["Foundation", "AppKit", "UserNotifications", "CoreMediaIO"] {
+    for framework in [
+        "Foundation",
+        "AppKit",
+        "UserNotifications",
+        "CoreMediaIO",
+        "CoreAudio",
+        "CoreGraphics",
+        "Network",
+        "Intents",
+        "IOKit",
+    ] {
         println!("cargo:rustc-link-lib=framework={framework}");
     }
 }
@@ -0,0 +1,68 @@
+//! Opening meeting links with a command of the user's choosing
+//! (`browser_command`, e.g. a specific Chrome profile) instead of the default
+//! browser.
+//!
+//! The link handlers in `src/native/notifications.m` and `src/native/tray.m`
+//! offer each http(s) link to [`on_open_link`] first, and open it with
+//! `NSWorkspace` as usual when it declines.
+
+use std::ffi::{CStr, c_char};
+use std::process::Command;
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use tracing::{error, info};
+
+/// The configured `browser_command`; `None` opens links the usual way.
+static COMMAND: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// Sets the command links are opened with (the link is appended as its last
+/// argument); `None` or an empty list restores the default browser. Call when
+/// the config is loaded.
+pub fn set_command(command: Option<Vec<String>>) {
+    *COMMAND.lock().unwrap_or_else(PoisonError::into_inner) = command.filter(|c| !c.is_empty());
+}
+
+/// Called by the ObjC link handlers with an http(s) link: runs the
+/// `browser_command` with the link appended and returns true, or returns
+/// false - open it the usual way - when none is set or it fails to start.
+pub extern "C" fn on_open_link(url: *const c_char) -> bool {
+    let url = unsafe { CStr::from_ptr(url) }.to_string_lossy().into_owned();
+    open(&url)
+}
+
+/// [`on_open_link`] for a Rust string.
+fn open(url: &str) -> bool {
+    let Some(command) = COMMAND.lock().unwrap_or_else(PoisonError::into_inner).clone() else {
+        return false;
+    };
+    match Command::new(&command[0]).args(&command[1..]).arg(url).spawn() {
+        Ok(mut child) => {
+            info!("opened {url} with {command:?}");
+            // reap it when it exits (`open` returns at once, a browser may not)
+            thread::spawn(move || child.wait());
+            true
+        }
+        Err(err) => {
+            error!("browser_command {command:?} failed, using the default browser: {err}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{open, set_command};
+
+    #[test]
+    fn command_or_fallback() {
+        set_command(None);
+        assert!(!open("https://meet.google.com/abc"));
+        set_command(Some(vec!["true".to_string(), "--profile".to_string()]));
+        assert!(open("https://meet.google.com/abc"));
+        // a command that can't start falls back to the default browser
+        set_command(Some(vec!["/nonexistent/browser".to_string()]));
+        assert!(!open("https://meet.google.com/abc"));
+        set_command(Some(Vec::new()));
+        assert!(!open("https://meet.google.com/abc"));
+    }
+}
@@ -1,14 +1,91 @@
-//! Camera-activity detection, backed by the implementation in
-//! `src/native/camera.m` (CoreMediaIO hardware API).
+//! Camera-activity (and screen-sharing) detection, backed by the
+//! implementation in `src/native/camera.m` (CoreMediaIO hardware API, and the
+//! CoreGraphics window list).
 //!
 //! Used to skip notifications/speech when the user is already on a call.
 
+use crate::config::Config;
+use crate::mic;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 unsafe extern "C" {
     fn camera_is_active() -> bool;
+    fn screen_is_shared() -> bool;
 }
 
+/// Default for how long a camera/mic reading is reused by the cached checks
+/// (`in_call_cache_secs`).
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Last reading of each device check, with when it was taken.
+type Reading = Mutex<Option<(Instant, bool)>>;
+static CAMERA: Reading = Mutex::new(None);
+static MIC: Reading = Mutex::new(None);
+static SCREEN: Reading = Mutex::new(None);
+
 /// Returns true if any video device is in use by some process, i.e. the user
 /// is likely on a call. Involves a device enumeration, so avoid hot loops.
 pub fn camera_active() -> bool {
     unsafe { camera_is_active() }
 }
+
+/// Returns true if a meeting app's screen-sharing controls are on screen. A
+/// heuristic matching window titles, which needs the Screen Recording
+/// permission (without it, always false).
+pub fn screen_shared() -> bool {
+    unsafe { screen_is_shared() }
+}
+
+/// [`camera_active`], reusing the last reading while it is younger than `ttl`.
+pub fn camera_active_cached(ttl: Duration) -> bool {
+    cached(&CAMERA, ttl, Instant::now(), camera_active)
+}
+
+/// The "in a call" heuristic: the camera is on or, unless `detect_mic` is
+/// off, the microphone is in use (audio-only calls), or with
+/// `detect_screen_share` the screen is being shared (presenting with both
+/// off). Readings are cached for `in_call_cache_secs` (default 5) to spare the
+/// device enumerations.
+pub fn in_call(config: &Config) -> bool {
+    let ttl = config.in_call_cache_secs.map_or(DEFAULT_CACHE_TTL, Duration::from_secs);
+    camera_active_cached(ttl)
+        || (config.detect_mic.unwrap_or(true) && cached(&MIC, ttl, Instant::now(), mic::mic_active))
+        || (config.detect_screen_share.unwrap_or(false) && cached(&SCREEN, ttl, Instant::now(), screen_shared))
+}
+
+/// The reading in `slot` if it was taken less than `ttl` before `now`, else a
+/// fresh one from `query`, stored for next time.
+fn cached(slot: &Reading, ttl: Duration, now: Instant, query: impl FnOnce() -> bool) -> bool {
+    let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((at, value)) = *slot
+        && now.duration_since(at) < ttl
+    {
+        return value;
+    }
+    let value = query();
+    *slot = Some((now, value));
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reading, cached};
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn readings_reused_within_ttl() {
+        let slot: Reading = Mutex::new(None);
+        let ttl = Duration::from_secs(5);
+        let start = Instant::now();
+        assert!(cached(&slot, ttl, start, || true));
+        // within the TTL the query isn't run at all
+        assert!(cached(&slot, ttl, start + Duration::from_secs(4), || unreachable!()));
+        // once expired it re-queries and stores the new reading
+        assert!(!cached(&slot, ttl, start + ttl, || false));
+        assert!(!cached(&slot, ttl, start + Duration::from_secs(6), || unreachable!()));
+        // a zero TTL disables the cache
+        assert!(cached(&slot, Duration::ZERO, start + ttl, || true));
+    }
+}
@@ -0,0 +1,168 @@
+//! Command-line modes that run once and exit instead of starting the menu
+//! bar app, for debugging a setup from a terminal:
+//!
+//! - `nextcall --check`: fetch the calendar and print the next call.
+//! - `nextcall --json`: the same as JSON, for status bar scripts.
+//! - `nextcall --say <text>`: speak `text` as an announcement would.
+
+use crate::ical::NextEvent;
+use crate::{config, ical, logic, say};
+use chrono::{DateTime, Utc};
+
+/// Runs the mode selected by `args` (without the program name), returning its
+/// exit code, or `None` with no arguments: start the app as usual.
+pub fn run(mut args: impl Iterator<Item = String>) -> Option<i32> {
+    let code = match args.next()?.as_str() {
+        "--check" => check(),
+        "--json" => json(),
+        "--say" => match args.next() {
+            Some(text) => say_text(&text),
+            None => usage("--say needs the text to speak"),
+        },
+        arg => usage(&format!("unknown argument {arg:?}")),
+    };
+    Some(code)
+}
+
+/// Prints `problem` and the usage, returning the usage-error exit code.
+fn usage(problem: &str) -> i32 {
+    eprintln!("{problem}\nusage: nextcall [--check | --json | --say <text>]");
+    2
+}
+
+/// Loads the config, or prints why it can't be loaded.
+fn load_config() -> Option<config::Config> {
+    match config::get_config() {
+        Ok(Some(config)) => Some(config),
+        Ok(None) => {
+            eprintln!("no config file found, create ~/nextcall.toml");
+            None
+        }
+        Err(err) => {
+            eprintln!("{err:#}");
+            None
+        }
+    }
+}
+
+/// Fetches the calendar once and prints the next call: 0 if the fetch
+/// succeeded (with or without an upcoming call), 1 otherwise. The fetch's
+/// details are logged to stderr.
+fn check() -> i32 {
+    let Some(config) = load_config() else { return 1 };
+    let now = Utc::now();
+    let Ok(next_call) = fetch_next_call(&config, now) else {
+        return 1;
+    };
+    match next_call {
+        Some(event) => {
+            println!("Next call: {}", event.summary);
+            println!(
+                "Starts:    {} {}",
+                logic::local_date_string(event.start_time),
+                logic::local_time_string(event.start_time)
+            );
+            println!("Link:      {}", event.video_link);
+            if let Some(app_link) = &event.app_link {
+                println!("App link:  {app_link}");
+            }
+        }
+        None => println!("No upcoming calls"),
+    }
+    0
+}
+
+/// Fetches the calendar once and prints the next call as a JSON object (see
+/// [`call_json`]): 0 if there is one, 3 with no upcoming call, 1 if the fetch
+/// failed.
+fn json() -> i32 {
+    let Some(config) = load_config() else { return 1 };
+    let now = Utc::now();
+    match fetch_next_call(&config, now) {
+        Ok(Some(event)) => {
+            println!("{}", call_json(&event, now));
+            0
+        }
+        Ok(None) => {
+            eprintln!("no upcoming calls");
+            3
+        }
+        Err(()) => 1,
+    }
+}
+
+/// The fields a script needs: `minutes_until` is whole minutes to the start,
+/// negative once the call has begun.
+fn call_json(event: &NextEvent, now: DateTime<Utc>) -> serde_json::Value {
+    serde_json::json!({
+        "summary": event.summary,
+        "start_time": event.start_time.to_rfc3339(),
+        "minutes_until": (event.start_time - now).num_minutes(),
+        "video_link": event.video_link,
+        "app_link": event.app_link,
+    })
+}
+
+/// Fetches the calendar once and selects the next call as the app would;
+/// `Err` (with the reason printed) if the fetch failed.
+fn fetch_next_call(config: &config::Config, now: DateTime<Utc>) -> Result<Option<NextEvent>, ()> {
+    let mut feed = match ical::CalendarFeed::new(config) {
+        Ok(feed) => feed,
+        Err(err) => {
+            eprintln!("failed to create HTTP client: {err}");
+            return Err(());
+        }
+    };
+    if feed.fetch(now) {
+        eprintln!("failed to fetch the calendar, see the log above");
+        return Err(());
+    }
+    Ok(feed.cal(now).next_call)
+}
+
+/// Speaks `text` with the configured voice (ElevenLabs when a key is set,
+/// falling back to the built-in voice if the API fails, as in the app),
+/// returning once playback finishes: 0 on success, 1 on error.
+fn say_text(text: &str) -> i32 {
+    let Some(config) = load_config() else { return 1 };
+    match say::say_blocking(text, &config) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("failed to speak: {err:#}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::call_json;
+    use crate::ical::NextEvent;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn json_fields() {
+        let now = Utc.with_ymd_and_hms(2026, 7, 9, 9, 8, 30).unwrap();
+        let event = NextEvent {
+            start_time: Utc.with_ymd_and_hms(2026, 7, 9, 10, 0, 0).unwrap(),
+            end_time: None,
+            summary: "Standup".to_string(),
+            video_link: "https://meet.google.com/abc-defg-hij".to_string(),
+            app_link: None,
+            organizer: None,
+            attendee_count: None,
+            all_day: false,
+            say_override: None,
+        };
+        assert_eq!(
+            call_json(&event, now),
+            serde_json::json!({
+                "summary": "Standup",
+                "start_time": "2026-07-09T10:00:00+00:00",
+                "minutes_until": 51,
+                "video_link": "https://meet.google.com/abc-defg-hij",
+                "app_link": null,
+            })
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Focus (Do Not Disturb) detection, backed by the implementation in
+//! `src/native/focus.m` (Intents framework).
+//!
+//! Used to keep speech silent while a Focus is on (`respect_focus`).
+
+unsafe extern "C" {
+    fn focus_startup();
+    fn focus_is_active() -> bool;
+}
+
+/// Requests access to the Focus status (a one-time system prompt). Call once
+/// at startup; until access is granted [`focus_active`] reports false.
+pub fn startup() {
+    unsafe { focus_startup() }
+}
+
+/// Returns true if a Focus mode is currently on.
+pub fn focus_active() -> bool {
+    unsafe { focus_is_active() }
+}
@@ -0,0 +1,207 @@
+//! The record of joined calls, for seeing attendance later.
+//!
+//! Each Join - a notification click or its Join button, or the menu's
+//! "Join: <next call>" - is reported to [`on_join`] by the ObjC link handlers
+//! once the link has opened, and appended to `~/.nextcall-joins.log`, one
+//! tab-separated line per join: when it happened (RFC 3339), the call's start
+//! (unix seconds, 0 if unknown) and the link. "About Nextcall" shows the day's
+//! tally from [`attendance`]. The log keeps the last [`KEEP_DAYS`] days; only
+//! today's joins are held in memory.
+
+use crate::config;
+use crate::ical::local_date;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::ffi::{CStr, c_char};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use tracing::{info, warn};
+
+/// How many days of joins the log keeps; older ones are dropped by [`load`].
+const KEEP_DAYS: i64 = 90;
+
+/// One recorded join.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Join {
+    pub joined_at: DateTime<Utc>,
+    /// The joined call's start, identifying it; `None` if unknown.
+    pub start: Option<DateTime<Utc>>,
+    pub url: String,
+}
+
+impl Join {
+    /// The log line for this join, without the newline.
+    fn to_line(&self) -> String {
+        let start = self.start.map_or(0, |start| start.timestamp());
+        format!("{}\t{start}\t{}", self.joined_at.to_rfc3339(), self.url)
+    }
+
+    /// Whether this join was made on `now`'s local day.
+    fn is_today(&self, now: DateTime<Utc>) -> bool {
+        local_date(self.joined_at) == local_date(now)
+    }
+
+    /// Parses a log line; `None` for a malformed one.
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(3, '\t');
+        let joined_at = DateTime::parse_from_rfc3339(fields.next()?).ok()?.to_utc();
+        let start: i64 = fields.next()?.parse().ok()?;
+        Some(Self {
+            joined_at,
+            start: (start != 0).then(|| DateTime::from_timestamp(start, 0)).flatten(),
+            url: fields.next()?.to_string(),
+        })
+    }
+}
+
+/// Today's joins: those read by [`load`] plus those recorded since.
+static JOINS: Mutex<Vec<Join>> = Mutex::new(Vec::new());
+
+/// `~/.nextcall-joins.log`; `None` without a home directory.
+fn path() -> Option<PathBuf> {
+    config::home()
+        .ok()
+        .map(|home| PathBuf::from(home).join(".nextcall-joins.log"))
+}
+
+/// Reads the joins recorded in `path`, oldest first, skipping malformed lines.
+pub fn read(path: &Path) -> io::Result<Vec<Join>> {
+    Ok(fs::read_to_string(path)?.lines().filter_map(Join::from_line).collect())
+}
+
+/// Appends `join` to the log at `path`, creating it if need be.
+fn append(path: &Path, join: &Join) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", join.to_line())
+}
+
+/// Loads today's recorded joins, so [`attendance`] counts those from before
+/// this launch. Call once at startup; a missing or unreadable log just starts
+/// empty.
+pub fn load() {
+    let Some(path) = path() else { return };
+    match load_from(&path, Utc::now()) {
+        Ok(joins) => *JOINS.lock().unwrap_or_else(PoisonError::into_inner) = joins,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => warn!("failed to read join history {}: {err}", path.display()),
+    }
+}
+
+/// Reads the log at `path`, rewriting it without joins older than
+/// [`KEEP_DAYS`] if it has any, and returns those made today.
+fn load_from(path: &Path, now: DateTime<Utc>) -> io::Result<Vec<Join>> {
+    let mut joins = read(path)?;
+    let logged = joins.len();
+    joins.retain(|join| now.signed_duration_since(join.joined_at) < TimeDelta::days(KEEP_DAYS));
+    if joins.len() < logged {
+        let lines: String = joins.iter().map(|join| format!("{}\n", join.to_line())).collect();
+        if let Err(err) = fs::write(path, lines) {
+            warn!("failed to trim join history {}: {err}", path.display());
+        }
+    }
+    joins.retain(|join| join.is_today(now));
+    Ok(joins)
+}
+
+/// Called by the ObjC link handlers once a call's link has opened: its start
+/// (unix seconds, 0 if unknown) and link. Records it in memory at once and
+/// appends it to the log on a separate thread, so a slow or failing write
+/// never holds up the caller, which may be the main thread.
+pub extern "C" fn on_join(start_ts: i64, url: *const c_char) {
+    let url = unsafe { CStr::from_ptr(url) }.to_string_lossy().into_owned();
+    let join = Join {
+        joined_at: Utc::now(),
+        start: (start_ts != 0).then(|| DateTime::from_timestamp(start_ts, 0)).flatten(),
+        url,
+    };
+    info!(
+        "joined {} ({})",
+        join.url,
+        join.start.map_or_else(|| "unknown call".to_string(), |s| s.to_string())
+    );
+    {
+        let mut joins = JOINS.lock().unwrap_or_else(PoisonError::into_inner);
+        // yesterday's joins no longer count
+        joins.retain(|earlier| earlier.is_today(join.joined_at));
+        joins.push(join.clone());
+    }
+    let Some(path) = path() else { return };
+    thread::spawn(move || {
+        if let Err(err) = append(&path, &join) {
+            warn!("failed to record join in {}: {err}", path.display());
+        }
+    });
+}
+
+/// "Joined N of M calls today", where `calls` are the starts of today's calls
+/// (see [`CalendarFeed::calls_today`](crate::ical::CalendarFeed::calls_today)).
+pub fn attendance(calls: &[DateTime<Utc>]) -> String {
+    let joins = JOINS.lock().unwrap_or_else(PoisonError::into_inner);
+    format!("Joined {} of {} calls today", joined(&joins, calls), calls.len())
+}
+
+/// How many of `calls` (by start) were joined: each counts once, however
+/// often it was joined.
+fn joined(joins: &[Join], calls: &[DateTime<Utc>]) -> usize {
+    calls
+        .iter()
+        .filter(|start| joins.iter().any(|join| join.start == Some(**start)))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Join, KEEP_DAYS, append, joined, load_from, read};
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use std::fs;
+
+    #[test]
+    fn joins_round_trip_and_count_once() {
+        let path = std::env::temp_dir().join(format!("nextcall-joins-test-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let standup = Utc.with_ymd_and_hms(2026, 7, 9, 9, 0, 0).unwrap();
+        let review = Utc.with_ymd_and_hms(2026, 7, 9, 14, 0, 0).unwrap();
+        let join = |minute, start| Join {
+            joined_at: Utc.with_ymd_and_hms(2026, 7, 9, 9, minute, 0).unwrap(),
+            start,
+            url: "https://meet.google.com/abc".to_string(),
+        };
+        let joins = [join(1, Some(standup)), join(2, Some(standup)), join(3, None)];
+        for j in &joins {
+            append(&path, j).unwrap();
+        }
+        // a torn or foreign line is skipped, not fatal
+        fs::write(&path, format!("{}garbage\n", fs::read_to_string(&path).unwrap())).unwrap();
+        let read_back = read(&path).unwrap();
+        assert_eq!(read_back, joins);
+        // the standup joined twice counts once; the review wasn't joined
+        assert_eq!(joined(&read_back, &[standup, review]), 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn load_trims_old_joins_and_keeps_today() {
+        let path = std::env::temp_dir().join(format!("nextcall-joins-trim-test-{}.log", std::process::id()));
+        let now = Utc.with_ymd_and_hms(2026, 7, 9, 12, 30, 0).unwrap();
+        let join = |ago: TimeDelta| Join {
+            joined_at: now - ago,
+            start: None,
+            url: "https://meet.google.com/abc".to_string(),
+        };
+        let (old, yesterday, today) = (
+            join(TimeDelta::days(KEEP_DAYS + 1)),
+            join(TimeDelta::days(1)),
+            join(TimeDelta::minutes(5)),
+        );
+        let _ = fs::remove_file(&path);
+        for j in [&old, &yesterday, &today] {
+            append(&path, j).unwrap();
+        }
+        assert_eq!(load_from(&path, now).unwrap(), std::slice::from_ref(&today));
+        // the log drops only what's past keeping
+        assert_eq!(read(&path).unwrap(), [yesterday, today]);
+        fs::remove_file(&path).unwrap();
+    }
+}
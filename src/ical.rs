@@ -1,28 +1,56 @@
-use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use crate::config::{self, Config};
+use crate::vtimezone::{self, Timezones};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
 use chrono_tz::Tz;
 use ical::IcalParser;
 use ical::parser::ical::component::IcalEvent;
 use ical::property::Property;
-use std::collections::HashMap;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::io::BufReader;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::{Duration, Instant};
-use tracing::{info, warn};
+use tracing::{debug, debug_span, info, warn};
 
 /// A concrete occurrence of a calendar event with a video link.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NextEvent {
     pub start_time: DateTime<Utc>,
+    /// From DTEND or DURATION; `None` when the event has neither, in which
+    /// case only the `join_grace_minutes` window applies.
+    pub end_time: Option<DateTime<Utc>>,
     pub summary: String,
     pub video_link: String,
+    /// Native-app deep link for the same meeting (`zoommtg://`, `msteams:`),
+    /// opened in preference to `video_link`; see [`to_launch_url`].
+    pub app_link: Option<String>,
+    /// The `ORGANIZER`'s address, without `mailto:`.
+    #[serde(default)]
+    pub organizer: Option<String>,
+    /// How many `ATTENDEE`s the invite lists; `None` without any.
+    #[serde(default)]
+    pub attendee_count: Option<usize>,
+    /// A date-only (`VALUE=DATE`) event, whose start is just midnight UTC.
+    #[serde(default)]
+    pub all_day: bool,
+    /// What to say instead of the generated announcement, from a
+    /// `NEXTCALL-SAY:` line in the description; see [`say_override`].
+    #[serde(default)]
+    pub say_override: Option<String>,
 }
 
 /// What the rest of the app needs from the calendar right now.
 #[derive(Debug, Clone, Default)]
 pub struct Cal {
-    /// Earliest event that is upcoming or started <10 min ago; drives the
-    /// countdown, the status line and the alerts.
+    /// Earliest event that is upcoming or started within
+    /// `join_grace_minutes`; drives the countdown, the status line and the
+    /// alerts.
     pub next_call: Option<NextEvent>,
 }
 
@@ -72,14 +100,16 @@ impl fmt::Display for CalendarError {
     }
 }
 
-/// Alert window: events that started less than this many minutes ago still
-/// count as `next_call`, so alerts can fire (one per minute since start).
+/// Default alert window: alerts fire once a minute for this many minutes after
+/// start (see `post_start_reminders`). Also the default `join_grace_minutes`:
+/// events that started less than this many minutes ago still count as
+/// `next_call`.
 pub const NEXT_MAX_AGE_MINUTES: i64 = 10;
 
-/// Parse-time lookback: occurrences up to this old are kept as candidates.
-/// Generously exceeds [`NEXT_MAX_AGE_MINUTES`] plus the worst-case cache age,
-/// so per-tick selection never misses a recently started event.
-const LOOKBACK_MINUTES: i64 = 60;
+/// Parse-time lookback beyond the grace window: occurrences up to grace +
+/// this old are kept as candidates. Generously exceeds the worst-case cache
+/// age, so per-tick selection never misses a recently started event.
+const LOOKBACK_MARGIN_MINUTES: i64 = 50;
 
 /// How many upcoming occurrences of a recurring event to consider. 2 covers
 /// the "one in progress + the next one upcoming" case (e.g. a daily standup).
@@ -89,9 +119,30 @@ const RECURRING_OCCURRENCE_LIMIT: u16 = 2;
 /// often enough to catch a meeting moved or cancelled at the last minute.
 const NEAR_EVENT_TTL: Duration = Duration::from_secs(60);
 
-/// Cache TTL otherwise; also how often a fetch failure is re-reported.
+/// Cache TTL otherwise.
 const IDLE_TTL: Duration = Duration::from_secs(180);
 
+/// Retry delay after the first failed fetch, doubling with each further
+/// consecutive failure up to [`MAX_BACKOFF`] (see [`backoff`]).
+const FIRST_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Cap on the retry delay during a long outage. Reconnecting cuts it short:
+/// main expires the cache on `Wake::NetworkUp`.
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// Default timeout for calendar requests (`http_timeout_secs`), used both to
+/// connect and as the total for the request, redirects and body included, so
+/// a dead connection (e.g. a dropped VPN) can't stall the main loop.
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Redirects followed per calendar request, e.g. a 302 to a temporary signed
+/// URL; a longer chain fails as a network error.
+const MAX_REDIRECTS: usize = 10;
+
+/// The on-disk cache is ignored once older than this: a day-old calendar is
+/// still useful offline, older data is more likely wrong than right.
+const DISK_CACHE_MAX_AGE: TimeDelta = TimeDelta::hours(24);
+
 /// Slack on the expiry check: a cache expiring within this margin of a fetch
 /// attempt is refreshed now rather than a whole sleep later. Without it the
 /// TTL phase-slips against the tick cadence (expiry lands seconds after an
@@ -103,27 +154,167 @@ const EXPIRY_SLACK: Duration = Duration::from_secs(15);
 /// occurrences, so the main loop can call [`CalendarFeed::fetch`] every tick
 /// (possibly every few seconds) while the network is hit at most once per TTL.
 pub struct CalendarFeed {
-    url: String,
+    /// Where the feed's bytes come from: [`HttpSource`] outside tests.
+    source: Box<dyn CalendarSource>,
+    /// Parse and selection settings from the config.
+    options: Options,
     /// Expanded occurrences from the last successful fetch.
     candidates: Vec<NextEvent>,
+    /// Starts of the calls on the day of the last successful fetch, earlier
+    /// ones included, for [`Self::calls_today`].
+    day_calls: Vec<DateTime<Utc>>,
     /// When the cache expires and the next `get` fetches again.
     expires: Instant,
+    /// Where the last successful fetch is persisted (`~/.nextcall-cache.json`);
+    /// `None` disables the disk cache.
+    disk_cache: Option<PathBuf>,
+    /// [`url_fingerprint`] of the calendar URL, tagging the disk cache so
+    /// another calendar's cache is never loaded.
+    calendar_id: u64,
+    /// Set once a fetch succeeded or the disk cache was consulted, so the
+    /// disk cache is only a startup fallback, never resurrected later.
+    primed: bool,
+    /// Set once the candidates reflect a calendar: a fetch succeeded or the
+    /// disk cache was loaded. See [`Self::loaded`].
+    loaded: bool,
+    /// Consecutive failed fetches, setting the retry backoff; 0 after a success.
+    failures: u32,
+    /// The last failure's message, so a repeat of it is logged quietly.
+    last_error: Option<String>,
+}
+
+/// Fetches the raw feed for [`CalendarFeed`], so its refresh and failure
+/// handling can be exercised without a server.
+trait CalendarSource: Send {
+    /// The feed's bytes, or why they couldn't be fetched.
+    fn fetch_bytes(&self) -> Result<bytes::Bytes, CalendarError>;
+}
+
+/// The calendar at `ical_url`, fetched over HTTP(S).
+struct HttpSource {
+    url: String,
+    /// Basic auth from `ical_username`/`ical_password`; credentials embedded
+    /// in the URL are applied by reqwest itself.
+    credentials: Option<Credentials>,
+    /// Reused across fetches; carries the request timeouts.
+    client: reqwest::blocking::Client,
+}
+
+impl CalendarSource for HttpSource {
+    fn fetch_bytes(&self) -> Result<bytes::Bytes, CalendarError> {
+        fetch_bytes(&self.client, &self.url, self.credentials.as_ref())
+    }
+}
+
+/// HTTP basic auth credentials for the calendar URL.
+#[derive(Debug)]
+struct Credentials {
+    username: String,
+    password: Option<String>,
+}
+
+impl Credentials {
+    /// From `ical_username`/`ical_password`; `None` when neither is set.
+    fn new(config: &Config) -> Option<Self> {
+        if config.ical_username.is_none() && config.ical_password.is_none() {
+            return None;
+        }
+        Some(Self {
+            username: config.ical_username.clone().unwrap_or_default(),
+            password: config.ical_password.clone(),
+        })
+    }
+}
+
+/// The feed's settings from the config, applied on every fetch and tick.
+#[derive(Debug)]
+struct Options {
+    /// The user's address; events they declined are skipped.
+    my_email: Option<String>,
+    /// Minutes after its start an event still counts as `next_call`.
+    grace_minutes: i64,
+    /// `video_link_hosts`: the only hosts (substrings) a video link may have.
+    video_link_hosts: Option<Vec<String>>,
+    /// `include_all_day`: all-day events may be selected too.
+    include_all_day: bool,
+    /// `skip_tentative`: events with `STATUS:TENTATIVE` are ignored.
+    skip_tentative: bool,
+    /// `ignore_summary_patterns`, lowercased: globs for titles to skip.
+    ignore_summary_patterns: Vec<String>,
+}
+
+impl Options {
+    /// The options from `config`, with defaults for unset settings.
+    fn new(config: &Config) -> Self {
+        Self {
+            my_email: config.my_email.clone(),
+            grace_minutes: config.join_grace_minutes.unwrap_or(NEXT_MAX_AGE_MINUTES),
+            video_link_hosts: config.video_link_hosts.clone(),
+            include_all_day: config.include_all_day.unwrap_or(false),
+            skip_tentative: config.skip_tentative.unwrap_or(false),
+            ignore_summary_patterns: config
+                .ignore_summary_patterns
+                .iter()
+                .flatten()
+                .map(|pattern| pattern.trim().to_lowercase())
+                .collect(),
+        }
+    }
+
+    /// The first `ignore_summary_patterns` entry matching `summary`, if any.
+    fn ignored_by(&self, summary: &str) -> Option<&str> {
+        let summary = summary.trim().to_lowercase();
+        self.ignore_summary_patterns
+            .iter()
+            .find(|pattern| glob_match(pattern, &summary))
+            .map(String::as_str)
+    }
+
+    /// How old an occurrence may be and still be kept as a candidate.
+    fn lookback(&self) -> TimeDelta {
+        TimeDelta::minutes(self.grace_minutes + LOOKBACK_MARGIN_MINUTES)
+    }
+}
+
+/// The disk cache's contents: the last successful fetch's candidates.
+#[derive(Serialize, Deserialize)]
+struct DiskCache {
+    fetched_at: DateTime<Utc>,
+    /// The [`url_fingerprint`] of the calendar it was fetched from.
+    calendar_id: u64,
+    candidates: Vec<NextEvent>,
 }
 
 impl CalendarFeed {
     /// A feed whose cache is empty and already expired: the first [`Self::fetch`] fetches.
-    pub fn new(url: String) -> Self {
-        Self {
-            url,
+    /// Fails only if the HTTP client can't be built (e.g. TLS backend init).
+    pub fn new(config: &Config) -> reqwest::Result<Self> {
+        Ok(Self {
+            source: Box::new(HttpSource {
+                url: config.ical_url.clone(),
+                credentials: Credentials::new(config),
+                client: http_client(config)?,
+            }),
+            options: Options::new(config),
             candidates: Vec::new(),
+            day_calls: Vec::new(),
             expires: Instant::now(),
-        }
+            disk_cache: config::home()
+                .ok()
+                .map(|home| PathBuf::from(home).join(".nextcall-cache.json")),
+            calendar_id: url_fingerprint(&config.ical_url),
+            primed: false,
+            loaded: false,
+            failures: 0,
+            last_error: None,
+        })
     }
 
     /// Refreshes the cache if it has expired, returning any fetch error. On
-    /// failure the stale candidates are kept; the expiry is bumped either
-    /// way, so a persistent outage surfaces one error per TTL rather than one
-    /// per tick. Read the resulting state with [`Self::cal`].
+    /// failure the stale candidates are kept and the next attempt backs off
+    /// exponentially ([`backoff`]), so a persistent outage doesn't hammer the
+    /// server; a repeat of the same error is logged at info level. Read the
+    /// resulting state with [`Self::cal`].
     ///
     /// Returns `true` if there was a fetch error.
     pub fn fetch(&mut self, now: DateTime<Utc>) -> bool {
@@ -131,21 +322,65 @@ impl CalendarFeed {
         let should_fetch = fetch_start + EXPIRY_SLACK >= self.expires;
         if should_fetch {
             let mut fetch_error = None;
-            match fetch_candidates(&self.url, now) {
-                Ok(candidates) => self.candidates = candidates,
-                Err(e) => fetch_error = Some(e),
+            // the parse's counts, for the success log line
+            let mut counts = String::new();
+            match fetch_candidates(self.source.as_ref(), now, &self.options) {
+                Ok(parsed) => {
+                    if let Some(path) = &self.disk_cache
+                        && let Err(e) = save_disk_cache(path, self.calendar_id, &parsed.candidates, now)
+                    {
+                        warn!("failed to write calendar cache {}: {e}", path.display());
+                    }
+                    counts = format!(
+                        "{} events, {} upcoming without a video link",
+                        parsed.events, parsed.upcoming_without_link
+                    );
+                    self.candidates = parsed.candidates;
+                    self.day_calls = parsed.day_calls;
+                    self.loaded = true;
+                }
+                Err(e) => {
+                    // offline at startup: fall back to the last run's calendar
+                    if !self.primed
+                        && let Some(candidates) = self
+                            .disk_cache
+                            .as_deref()
+                            .and_then(|p| load_disk_cache(p, self.calendar_id, now))
+                    {
+                        info!("using cached calendar from disk ({} events)", candidates.len());
+                        self.candidates = candidates;
+                        self.loaded = true;
+                    }
+                    fetch_error = Some(e);
+                }
             }
+            self.primed = true;
             let cal = self.cal(now);
             let near_event = cal
                 .next_call
                 .as_ref()
                 .is_some_and(|e| e.start_time.signed_duration_since(now) < TimeDelta::minutes(NEXT_MAX_AGE_MINUTES));
-            self.expires = fetch_start + if near_event { NEAR_EVENT_TTL } else { IDLE_TTL };
             if let Some(err) = &fetch_error {
-                warn!("{}: {}", err.subtitle(), err);
+                self.failures += 1;
+                let retry = backoff(self.failures);
+                self.expires = fetch_start + retry;
+                let message = format!("{}: {}", err.subtitle(), err);
+                if self.last_error.as_ref() == Some(&message) {
+                    info!(
+                        "{message} ({} failures in a row, retrying in {}s)",
+                        self.failures,
+                        retry.as_secs()
+                    );
+                } else {
+                    warn!("{message} (retrying in {}s)", retry.as_secs());
+                    self.last_error = Some(message);
+                }
             } else {
+                self.failures = 0;
+                self.last_error = None;
+                self.expires = fetch_start + if near_event { NEAR_EVENT_TTL } else { IDLE_TTL };
                 info!(
-                    "fetched calendar in {:.2}s, {}",
+                    "fetched calendar in {:.2}s, {} ({counts})",
                     fetch_start.elapsed().as_secs_f64(),
                     cal
                 );
@@ -156,50 +391,306 @@ impl CalendarFeed {
         }
     }
 
+    /// Whether the latest fetch succeeded; false before the first.
+    pub fn fetched_ok(&self) -> bool {
+        self.primed && self.failures == 0
+    }
+
+    /// Whether [`Self::cal`] reflects a calendar yet: a fetch has succeeded
+    /// or the disk cache stood in for a failed first one. Stays true once set.
+    pub fn loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// Expires the cache so the next [`Self::fetch`] hits the network, e.g.
+    /// when connectivity returns after a failed fetch.
+    pub fn expire(&mut self) {
+        self.expires = Instant::now();
+    }
+
     /// Pure window selection: `next_call` is the earliest candidate that is
-    /// upcoming or started within the last [`NEXT_MAX_AGE_MINUTES`].
+    /// upcoming or started within the grace window (`join_grace_minutes`) and
+    /// has not already ended (a 5 minute standup stops counting after 5 minutes).
+    /// All-day events are skipped unless `include_all_day` is set.
     pub fn cal(&self, now: DateTime<Utc>) -> Cal {
-        let next_call = self
+        Cal {
+            next_call: self.upcoming(now, 1).pop(),
+        }
+    }
+
+    /// The soonest `limit` calls by start time, selected like `next_call`
+    /// (which is the first of them) - the tray's "Upcoming" agenda. Calls
+    /// starting together are ordered by summary, then link, so the pick
+    /// doesn't depend on the order of the feed. (Events without a video link
+    /// never get this far, so a linked event always beats an unlinked one.)
+    pub fn upcoming(&self, now: DateTime<Utc>, limit: usize) -> Vec<NextEvent> {
+        let mut upcoming: Vec<NextEvent> = self
             .candidates
             .iter()
             // positive duration = the candidate started that long ago
-            .filter(|c| now.signed_duration_since(c.start_time).num_minutes() <= NEXT_MAX_AGE_MINUTES)
-            .min_by_key(|c| c.start_time)
-            .cloned();
-        Cal { next_call }
+            .filter(|c| now.signed_duration_since(c.start_time).num_minutes() <= self.options.grace_minutes)
+            .filter(|c| c.end_time.is_none_or(|end| end > now))
+            .filter(|c| self.options.include_all_day || !c.all_day)
+            .cloned()
+            .collect();
+        upcoming
+            .sort_by(|a, b| (a.start_time, &a.summary, &a.video_link).cmp(&(b.start_time, &b.summary, &b.video_link)));
+        upcoming.truncate(limit);
+        upcoming
+    }
+
+    /// Starts of today's calls (local time), including those already over -
+    /// the "M" of the attendance line in "About Nextcall".
+    pub fn calls_today(&self, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let today = local_date(now);
+        self.day_calls
+            .iter()
+            .copied()
+            .filter(|start| local_date(*start) == today)
+            .collect()
+    }
+}
+
+/// Rewrites `webcal://` and `webcals://` subscription links (as handed out
+/// by Apple Calendar) to `https://`, leaving the rest of the URL intact.
+/// Schemes other than http, https and webcal are rejected.
+fn normalize_url(url: &str) -> Result<String, CalendarError> {
+    let url = url.trim();
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return Err(CalendarError::NetworkError(format!(
+            "invalid calendar URL {url:?}: expected http://, https:// or webcal://"
+        )));
+    };
+    match scheme.to_ascii_lowercase().as_str() {
+        "http" | "https" => Ok(url.to_string()),
+        "webcal" | "webcals" => Ok(format!("https://{rest}")),
+        _ => Err(CalendarError::NetworkError(format!(
+            "unsupported calendar URL scheme {scheme:?}: expected http, https or webcal"
+        ))),
     }
 }
 
-/// Downloads the feed and expands it into candidate occurrences.
-fn fetch_candidates(url: &str, now: DateTime<Utc>) -> Result<Vec<NextEvent>, CalendarError> {
-    let response = reqwest::blocking::get(url).map_err(|e| CalendarError::NetworkError(e.to_string()))?;
+/// Rewrites a Zoom or Teams web link into the desktop client's URL scheme,
+/// so joining skips the browser hop: `https://<host>.zoom.us/j/<id>?pwd=<pwd>`
+/// becomes `zoommtg://<host>/join?confno=<id>&pwd=<pwd>`, and Teams'
+/// `https://teams.microsoft.com/l/...` becomes `msteams:/l/...`. `None` for
+/// other links or ones that don't parse; callers then use the https link.
+fn to_launch_url(link: &str) -> Option<String> {
+    let url = reqwest::Url::parse(link).ok()?;
+    let host = url.host_str()?;
+    if host == "zoom.us" || host.ends_with(".zoom.us") {
+        let id = url.path().strip_prefix("/j/")?.trim_end_matches('/');
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let mut launch = reqwest::Url::parse(&format!("zoommtg://{host}/join")).ok()?;
+        {
+            // re-encoded: a passcode may hold `&`, `+`, `#` or spaces
+            let mut query = launch.query_pairs_mut();
+            query.append_pair("action", "join").append_pair("confno", id);
+            if let Some((_, pwd)) = url.query_pairs().find(|(key, _)| key == "pwd") {
+                query.append_pair("pwd", &pwd);
+            }
+        }
+        Some(launch.into())
+    } else if host == "teams.microsoft.com" && url.path().starts_with("/l/") {
+        let query = url.query().map(|q| format!("?{q}")).unwrap_or_default();
+        Some(format!("msteams:{}{query}", url.path()))
+    } else {
+        None
+    }
+}
+
+/// Persists a successful fetch's candidates, stamped with the fetch time and
+/// the calendar they came from.
+fn save_disk_cache(path: &Path, calendar_id: u64, candidates: &[NextEvent], now: DateTime<Utc>) -> std::io::Result<()> {
+    let cache = DiskCache {
+        fetched_at: now,
+        calendar_id,
+        candidates: candidates.to_vec(),
+    };
+    fs::write(path, serde_json::to_vec(&cache)?)
+}
+
+/// The cached candidates if the file exists, parses, came from the calendar
+/// `calendar_id` identifies and is younger than [`DISK_CACHE_MAX_AGE`]; a
+/// missing, corrupt or foreign cache is just `None`.
+fn load_disk_cache(path: &Path, calendar_id: u64, now: DateTime<Utc>) -> Option<Vec<NextEvent>> {
+    let cache: DiskCache = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    (cache.calendar_id == calendar_id && now.signed_duration_since(cache.fetched_at) < DISK_CACHE_MAX_AGE)
+        .then_some(cache.candidates)
+}
+
+/// Identifies a calendar URL in the disk cache without writing the URL, which
+/// may embed credentials, to disk.
+fn url_fingerprint(url: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Fetches the feed from `source` and expands it into candidate occurrences. Timeouts
+/// surface as [`CalendarError::NetworkError`] like any other network failure.
+fn fetch_candidates(
+    source: &dyn CalendarSource,
+    now: DateTime<Utc>,
+    options: &Options,
+) -> Result<Parsed, CalendarError> {
+    let _span = debug_span!("fetch").entered();
+    let content = source.fetch_bytes()?;
+    parse_candidates(content.as_ref(), now, options)
+}
+
+/// Checks that `url` serves an iCal feed before it is saved: fetches it (with
+/// the config's timeouts) and requires at least one VCALENDAR.
+pub fn validate_url(url: &str, config: &Config) -> Result<(), CalendarError> {
+    let client = http_client(config).map_err(|e| CalendarError::NetworkError(e.to_string()))?;
+    let content = fetch_bytes(&client, url, Credentials::new(config).as_ref())?;
+    check_calendar(content.as_ref())
+}
+
+/// Errors unless `content` parses as at least one VCALENDAR.
+fn check_calendar(content: &[u8]) -> Result<(), CalendarError> {
+    match IcalParser::new(BufReader::new(decode_text(content).as_bytes())).next() {
+        Some(Ok(_)) => Ok(()),
+        Some(Err(e)) => Err(CalendarError::InvalidFormat(e.to_string())),
+        None => Err(CalendarError::InvalidFormat(
+            "not an iCal feed: no VCALENDAR found".to_string(),
+        )),
+    }
+}
+
+/// The feed as UTF-8 text: a leading byte-order mark (which some exporters
+/// write) is dropped, and content that isn't valid UTF-8 is read as Latin-1,
+/// the usual encoding of older exports, rather than rejected.
+fn decode_text(content: &[u8]) -> Cow<'_, str> {
+    let content = content.strip_prefix(b"\xef\xbb\xbf").unwrap_or(content);
+    match std::str::from_utf8(content) {
+        Ok(text) => Cow::Borrowed(text),
+        Err(_) => {
+            debug!("feed is not valid UTF-8, decoding as Latin-1");
+            Cow::Owned(content.iter().map(|&byte| char::from(byte)).collect())
+        }
+    }
+}
+
+/// Retry delay after `failures` (>= 1) consecutive failed fetches: 1, 2, 4,
+/// ... minutes, capped at [`MAX_BACKOFF`].
+fn backoff(failures: u32) -> Duration {
+    FIRST_BACKOFF
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF)
+}
+
+/// The HTTP client for calendar requests, with the config's timeouts,
+/// `ical_headers` and `proxy`, identifying itself as `nextcall/<version>` and
+/// following up to [`MAX_REDIRECTS`] redirects.
+fn http_client(config: &Config) -> reqwest::Result<reqwest::blocking::Client> {
+    let timeout = config
+        .http_timeout_secs
+        .map_or(DEFAULT_HTTP_TIMEOUT, Duration::from_secs);
+    // invalid names and values are rejected by `Config::validate`
+    let headers: HeaderMap = config
+        .ical_headers
+        .iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            let mut value = HeaderValue::from_str(value).ok()?;
+            // may well be a token: keep it out of Debug output
+            value.set_sensitive(true);
+            Some((HeaderName::from_bytes(name.as_bytes()).ok()?, value))
+        })
+        .collect();
+    let mut builder = reqwest::blocking::Client::builder()
+        .user_agent(concat!("nextcall/", env!("CARGO_PKG_VERSION")))
+        .default_headers(headers)
+        .connect_timeout(timeout)
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS));
+    if let Some(proxy) = config.proxy()? {
+        builder = builder.proxy(proxy);
+    }
+    builder.build()
+}
+
+/// GETs `url` (after [`normalize_url`]) with any basic auth `credentials`,
+/// returning the body of a 2xx response. `user:pass@` in the URL is sent as
+/// basic auth by reqwest itself. Redirects are followed by the client, so
+/// the status checked - and the body parsed - is the final response's.
+fn fetch_bytes(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    credentials: Option<&Credentials>,
+) -> Result<bytes::Bytes, CalendarError> {
+    let url = normalize_url(url)?;
+    let mut request = client.get(&url);
+    if let Some(credentials) = credentials {
+        request = request.basic_auth(&credentials.username, credentials.password.as_ref());
+    }
+    let response = request.send().map_err(|e| CalendarError::NetworkError(e.to_string()))?;
 
     let status = response.status();
+    if response.url().as_str() != url {
+        // only the host: a signed URL's query is a credential
+        debug!("redirected to {}", response.url().host_str().unwrap_or_default());
+    }
+    debug!("calendar responded {status}");
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(CalendarError::HttpStatus(format!(
+            "{status}: the calendar wants a login; check ical_username/ical_password \
+             (or user:password@ in the URL)"
+        )));
+    }
     if !status.is_success() {
         let status_text = response.text().unwrap_or_default();
         return Err(CalendarError::HttpStatus(format!("{status}: {status_text}",)));
     }
 
-    let content = response
+    let body = response
         .bytes()
         .map_err(|e| CalendarError::NetworkError(e.to_string()))?;
-
-    parse_candidates(content.as_ref(), now)
+    debug!("read {} bytes", body.len());
+    Ok(body)
 }
 
 /// Parses raw iCal bytes into candidate occurrences: every event occurrence
-/// with a video link from `now - 60min` onward (RRULE-expanded, with
-/// overridden and cancelled instances removed). No window selection here -
-/// that happens per tick in [`CalendarFeed::cal`].
-fn parse_candidates(content: &[u8], now: DateTime<Utc>) -> Result<Vec<NextEvent>, CalendarError> {
-    let parser = IcalParser::new(BufReader::new(content));
+/// with a video link from `now - lookback` onward (RRULE-expanded, with
+/// overridden, cancelled and declined instances removed, and duplicates -
+/// the same UID, else summary, at the same start - kept once). No window selection
+/// here - that happens per tick in [`CalendarFeed::cal`].
+fn parse_candidates(content: &[u8], now: DateTime<Utc>, options: &Options) -> Result<Parsed, CalendarError> {
+    let _span = debug_span!("parse").entered();
+    let lookback = options.lookback();
+    let today = local_date(now);
+    let midnight = |date: NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+            .map(|start| start.with_timezone(&Utc))
+    };
+    let day_start = midnight(today).unwrap_or(now - TimeDelta::days(1));
+    let window = Window {
+        start: day_start.min(now - lookback),
+        day_end: today
+            .succ_opt()
+            .and_then(midnight)
+            .unwrap_or(day_start + TimeDelta::days(2)),
+        lookback_start: now - lookback,
+        now,
+    };
+    let text = decode_text(content);
+    let parser = IcalParser::new(BufReader::new(text.as_bytes()));
 
     // Collect all events first: override instances (RECURRENCE-ID) must be
     // known before their master's rule is expanded, wherever they appear.
     let mut all_events: Vec<IcalEvent> = Vec::new();
+    let mut zones = Timezones::new();
     for calendar in parser {
         match calendar {
-            Ok(cal) => all_events.extend(cal.events),
+            Ok(cal) => {
+                zones.extend(vtimezone::parse_timezones(&cal.timezones));
+                all_events.extend(cal.events);
+            }
             Err(e) => return Err(CalendarError::InvalidFormat(e.to_string())),
         }
     }
@@ -211,67 +702,220 @@ fn parse_candidates(content: &[u8], now: DateTime<Utc>) -> Result<Vec<NextEvent>
     for event in &all_events {
         if let (Some(uid), Some(recurrence_id)) = (
             get_property(event, "UID"),
-            extract_datetime_property(event, "RECURRENCE-ID"),
+            extract_datetime_property(event, "RECURRENCE-ID", &zones),
         ) {
             overridden.entry(uid).or_default().push(recurrence_id);
         }
     }
 
+    debug!("parsed {} events, {} time zones", all_events.len(), zones.len());
     let mut candidates = Vec::new();
+    // (UID, else summary; start) of each candidate: the same invite can be in
+    // several of the merged calendars, and must only alert once
+    let mut seen: HashSet<(String, DateTime<Utc>)> = HashSet::new();
+    let mut upcoming_without_link = 0;
+    // starts of the day's calls, found like the candidates but from midnight
+    let mut day_calls = Vec::new();
+    let mut seen_today: HashSet<(String, DateTime<Utc>)> = HashSet::new();
     for event in &all_events {
+        let summary = get_event_summary(event);
         // Cancelled events (and cancelled single occurrences) stay in the feed
-        if get_property(event, "STATUS").as_deref() == Some("CANCELLED") {
+        let status = get_property(event, "STATUS").unwrap_or_default();
+        if status.eq_ignore_ascii_case("CANCELLED") {
+            debug!("skipping {summary:?}: cancelled");
+            continue;
+        }
+        if options.skip_tentative && status.eq_ignore_ascii_case("TENTATIVE") {
+            debug!("skipping {summary:?}: tentative");
+            continue;
+        }
+        // Declined invites stay in the feed too, often with a join link
+        if options
+            .my_email
+            .as_deref()
+            .is_some_and(|email| declined_by(event, email))
+        {
+            debug!("skipping {summary:?}: declined");
             continue;
         }
-        let Some(video_link) = get_video_link(event) else {
+        if let Some(pattern) = summary.as_deref().and_then(|summary| options.ignored_by(summary)) {
+            debug!("skipping {summary:?}: matches ignore pattern {pattern:?}");
+            continue;
+        }
+        // expanded once, for both the day's calls and the candidates
+        let starts = occurrences(event, &window, &overridden, &zones);
+        let Some(video_link) = get_video_link(event, options.video_link_hosts.as_deref()) else {
+            debug!("skipping {summary:?}: no video link");
+            // counted so the log shows polling works even with no calls
+            if starts.iter().any(|start| *start >= now) {
+                upcoming_without_link += 1;
+            }
             continue;
         };
-        for start_time in occurrences(event, now, &overridden) {
-            // positive = the occurrence started that long ago
-            if now.signed_duration_since(start_time).num_minutes() > LOOKBACK_MINUTES {
+        // every occurrence of a recurring event shares the master's length
+        let duration = event_duration(event, &zones);
+        let identity =
+            get_property(event, "UID").map_or_else(|| format!("summary {summary:?}"), |uid| format!("uid {uid}"));
+        if options.include_all_day || !is_all_day(event) {
+            for &start_time in &starts {
+                if local_date(start_time) == today && seen_today.insert((identity.clone(), start_time)) {
+                    day_calls.push(start_time);
+                }
+            }
+        }
+        let mut picked: Vec<_> = starts
+            .iter()
+            .copied()
+            .filter(|start| *start >= window.lookback_start)
+            .take(RECURRING_OCCURRENCE_LIMIT.into())
+            .collect();
+        // a long grace can fill the limit with calls that have since ended,
+        // so the next one to come is always kept too
+        if let Some(next) = starts.iter().copied().find(|start| *start >= now)
+            && !picked.contains(&next)
+        {
+            picked.push(next);
+        }
+        for start_time in picked {
+            if !seen.insert((identity.clone(), start_time)) {
+                debug!("skipping {summary:?} at {start_time}: duplicate");
                 continue;
             }
+            debug!("candidate {summary:?} at {start_time}");
             candidates.push(NextEvent {
                 start_time,
-                summary: get_event_summary(event).unwrap_or_else(|| "Unknown".to_string()),
+                end_time: duration.map(|d| start_time + d),
+                summary: summary.clone().unwrap_or_else(|| "Unknown".to_string()),
                 video_link: video_link.clone(),
+                app_link: to_launch_url(&video_link),
+                organizer: get_property(event, "ORGANIZER").map(|o| strip_mailto(&o).to_string()),
+                attendee_count: Some(event.properties.iter().filter(|p| p.name == "ATTENDEE").count())
+                    .filter(|n| *n > 0),
+                all_day: is_all_day(event),
+                say_override: get_property(event, "DESCRIPTION").and_then(|d| say_override(&d)),
             });
         }
     }
-    Ok(candidates)
+    Ok(Parsed {
+        candidates,
+        day_calls,
+        events: all_events.len(),
+        upcoming_without_link,
+    })
+}
+
+/// The local calendar date `dt` falls on.
+pub fn local_date(dt: DateTime<Utc>) -> NaiveDate {
+    dt.with_timezone(&Local).date_naive()
+}
+
+/// Whether the whole of `text` matches `pattern`, where `*` stands for any
+/// run of characters and `?` for any one; everything else is literal.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // the last `*` seen and the text position it has been stretched to
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // let the `*` swallow one more character and retry
+            star = Some((star_p, star_t + 1));
+            p = star_p + 1;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// A parsed feed: the join-able occurrences, plus counts for the log that
+/// tell an empty feed apart from one whose events just have no video links.
+#[derive(Debug)]
+struct Parsed {
+    candidates: Vec<NextEvent>,
+    /// Starts of the calls on `now`'s local day, earlier ones included.
+    day_calls: Vec<DateTime<Utc>>,
+    /// Every VEVENT in the feed, including those skipped.
+    events: usize,
+    /// Events (not cancelled or declined) with an occurrence still to come
+    /// but no video link.
+    upcoming_without_link: usize,
+}
+
+/// The span of occurrences a parse needs: the day's, for
+/// [`Parsed::day_calls`], and the next few from the lookback, for the
+/// candidates.
+struct Window {
+    /// The earlier of local midnight and `lookback_start`.
+    start: DateTime<Utc>,
+    /// Local midnight at the end of today.
+    day_end: DateTime<Utc>,
+    /// `now - lookback`, where candidates begin.
+    lookback_start: DateTime<Utc>,
+    /// The parse's `now`: the first occurrence from here is always kept.
+    now: DateTime<Utc>,
 }
 
 /// The concrete start times of an event that could matter now: the single
-/// DTSTART for a one-off event, or the expanded occurrences (from an hour ago
-/// onwards) of a recurring one.
+/// DTSTART for a one-off event, or the expanded occurrences (see
+/// [`expand_rrule`]) of a recurring one.
 fn occurrences(
     event: &IcalEvent,
-    now: DateTime<Utc>,
+    window: &Window,
     overridden: &HashMap<String, Vec<DateTime<Utc>>>,
+    zones: &Timezones,
 ) -> Vec<DateTime<Utc>> {
     if get_property(event, "RRULE").is_none() {
-        return extract_datetime(event).into_iter().collect();
+        return extract_datetime(event, zones).into_iter().collect();
     }
     let superseded = get_property(event, "UID")
         .and_then(|uid| overridden.get(&uid).cloned())
         .unwrap_or_default();
-    expand_rrule(event, now)
+    expand_rrule(event, window, zones)
         .into_iter()
         .filter(|start| !superseded.contains(start))
         .collect()
 }
 
-/// Expands a recurring event's rule to concrete occurrences from an hour ago
-/// onwards, limited to [`RECURRING_OCCURRENCE_LIMIT`]. EXDATEs are honoured
+/// Expands a recurring event's rule to concrete occurrences in one pass: all
+/// of those from `window.start` to the end of the day, and on until there are
+/// [`RECURRING_OCCURRENCE_LIMIT`] from the lookback and one from `window.now`
+/// (a long grace can fill the limit with finished ones). EXDATEs are honoured
 /// by the `rrule` crate; returns empty (with a warning) on unparseable rules.
-fn expand_rrule(event: &IcalEvent, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+///
+/// A DTSTART in a feed-defined zone (see [`crate::vtimezone`]), which the
+/// `rrule` crate can't resolve, is expanded as if its wall-clock times were
+/// UTC, then each occurrence is converted with the zone's own offsets - so
+/// a 10:00 meeting stays at 10:00 local across DST changes.
+fn expand_rrule(event: &IcalEvent, window: &Window, zones: &Timezones) -> Vec<DateTime<Utc>> {
+    let custom_zone = event
+        .properties
+        .iter()
+        .find(|p| p.name == "DTSTART")
+        .and_then(get_tzid)
+        .filter(|tzid| Tz::from_str(tzid).is_err())
+        .and_then(|tzid| Some((tzid, zones.get(tzid)?)));
+
     // The rrule crate parses raw iCalendar lines, so reconstruct the
     // recurrence-related lines of this event.
     let source = event
         .properties
         .iter()
         .filter(|p| ["DTSTART", "RRULE", "EXDATE", "RDATE"].contains(&p.name.as_str()))
-        .map(property_line)
+        .map(|p| match get_tzid(p) {
+            Some(tzid) if custom_zone.is_some_and(|(custom, _)| custom == tzid) => property_line(&with_tzid(p, "UTC")),
+            // normalized, as for one-off events
+            Some(tzid) => property_line(&with_tzid(p, tzid)),
+            None => property_line(p),
+        })
         .collect::<Vec<_>>()
         .join("\n");
 
@@ -286,9 +930,42 @@ fn expand_rrule(event: &IcalEvent, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
         }
     };
 
-    let window_start = (now - TimeDelta::minutes(LOOKBACK_MINUTES)).with_timezone(&rrule::Tz::UTC);
-    let result = rrule_set.after(window_start).all(RECURRING_OCCURRENCE_LIMIT);
-    result.dates.into_iter().map(|d| d.with_timezone(&Utc)).collect()
+    // iterated lazily, with the crate's iteration limits, so the pass stops
+    // as soon as it has what the window needs
+    let rrule_set = rrule_set.limit();
+    let mut starts = Vec::new();
+    let mut recent = 0;
+    let mut upcoming = false;
+    for occurrence in &rrule_set {
+        let start = match custom_zone {
+            // the expansion's "UTC" is the zone's wall clock
+            Some((_, zone)) => zone.to_utc(occurrence.naive_utc()),
+            None => occurrence.with_timezone(&Utc),
+        };
+        if start < window.start {
+            continue;
+        }
+        if start >= window.day_end && recent >= RECURRING_OCCURRENCE_LIMIT && upcoming {
+            break;
+        }
+        if start >= window.lookback_start {
+            recent += 1;
+        }
+        upcoming |= start >= window.now;
+        starts.push(start);
+    }
+    starts
+}
+
+/// A copy of `prop` with its `TZID` parameter replaced by `tzid`.
+fn with_tzid(prop: &Property, tzid: &str) -> Property {
+    let mut prop = prop.clone();
+    for (key, values) in prop.params.iter_mut().flatten() {
+        if key == "TZID" {
+            *values = vec![tzid.to_string()];
+        }
+    }
+    prop
 }
 
 /// Reconstructs a property's raw iCalendar line (`NAME;PARAM=VAL:VALUE`); the
@@ -308,27 +985,66 @@ fn property_line(prop: &Property) -> String {
     line
 }
 
+/// The event's length: DTEND - DTSTART, else its DURATION; `None` when it
+/// has neither (or they don't parse).
+fn event_duration(event: &IcalEvent, zones: &Timezones) -> Option<TimeDelta> {
+    if let (Some(start), Some(end)) = (
+        extract_datetime(event, zones),
+        extract_datetime_property(event, "DTEND", zones),
+    ) {
+        return Some(end - start);
+    }
+    parse_duration(&get_property(event, "DURATION")?)
+}
+
+/// Parses an RFC 5545 DURATION value such as `PT30M`, `PT1H30M`, `P1D` or
+/// `P2W` (optionally signed). Returns `None` for malformed values.
+fn parse_duration(value: &str) -> Option<TimeDelta> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let value = value.strip_prefix('P')?;
+    let mut total = TimeDelta::zero();
+    let mut digits = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            'T' if digits.is_empty() => in_time = true,
+            _ => {
+                let n: i64 = digits.parse().ok()?;
+                digits.clear();
+                total += match (c, in_time) {
+                    ('W', false) => TimeDelta::weeks(n),
+                    ('D', false) => TimeDelta::days(n),
+                    ('H', true) => TimeDelta::hours(n),
+                    ('M', true) => TimeDelta::minutes(n),
+                    ('S', true) => TimeDelta::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    // trailing digits without a unit are malformed
+    digits.is_empty().then_some(if negative { -total } else { total })
+}
+
 /// The event's literal start time (DTSTART), ignoring any recurrence rule.
-fn extract_datetime(event: &IcalEvent) -> Option<DateTime<Utc>> {
-    extract_datetime_property(event, "DTSTART")
+fn extract_datetime(event: &IcalEvent, zones: &Timezones) -> Option<DateTime<Utc>> {
+    extract_datetime_property(event, "DTSTART", zones)
 }
 
 /// Parses a datetime property (DTSTART, RECURRENCE-ID, ...) into UTC,
-/// handling `TZID=` parameters, `...Z` UTC values and date-only values.
-fn extract_datetime_property(event: &IcalEvent, name: &str) -> Option<DateTime<Utc>> {
+/// handling `TZID=` parameters (IANA names, else the feed's own VTIMEZONEs in
+/// `zones`), `...Z` UTC values, floating values (no zone: the machine's local
+/// time) and date-only values.
+fn extract_datetime_property(event: &IcalEvent, name: &str, zones: &Timezones) -> Option<DateTime<Utc>> {
     let dtstart_property = event.properties.iter().find(|p| p.name == name)?;
     let value = dtstart_property.value.as_ref()?;
 
     // Check if there's a TZID parameter
-    let tzid = dtstart_property.params.as_ref().and_then(|params| {
-        params.iter().find_map(|(key, values)| {
-            if key == "TZID" && !values.is_empty() {
-                Some(values[0].as_str())
-            } else {
-                None
-            }
-        })
-    });
+    let tzid = get_tzid(dtstart_property);
 
     // Clean the datetime string
     let cleaned = value.replace("-", "").replace(":", "");
@@ -345,6 +1061,9 @@ fn extract_datetime_property(event: &IcalEvent, name: &str) -> Option<DateTime<U
                     if let Some(local_dt) = tz.from_local_datetime(&naive_dt).earliest() {
                         return Some(local_dt.with_timezone(&Utc));
                     }
+                } else if let Some(zone) = zones.get(tz_name) {
+                    // a custom name (e.g. Outlook's "Customized Time Zone")
+                    return Some(zone.to_utc(naive_dt));
                 }
             }
         }
@@ -360,6 +1079,14 @@ fn extract_datetime_property(event: &IcalEvent, name: &str) -> Option<DateTime<U
         }
     }
 
+    // Handle floating datetime (no Z, no TZID): local time wherever the user is
+    if cleaned.contains('T') && cleaned.len() == 15 {
+        // Format: 20231225T120000
+        if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(&cleaned, "%Y%m%dT%H%M%S") {
+            return Some(Local.from_local_datetime(&naive_dt).earliest()?.with_timezone(&Utc));
+        }
+    }
+
     // Handle date only (no time)
     if cleaned.len() == 8 {
         // Format: 20231225 (date only)
@@ -372,6 +1099,52 @@ fn extract_datetime_property(event: &IcalEvent, name: &str) -> Option<DateTime<U
     None
 }
 
+/// Whether the event's DTSTART is a date without a time (`VALUE=DATE`, or a
+/// bare `YYYYMMDD` value): an all-day event.
+fn is_all_day(event: &IcalEvent) -> bool {
+    event
+        .properties
+        .iter()
+        .find(|p| p.name == "DTSTART")
+        .is_some_and(|prop| {
+            get_param(prop, "VALUE") == Some("DATE") || prop.value.as_deref().is_some_and(|v| !v.contains('T'))
+        })
+}
+
+/// The first value of a property parameter (`TZID`, `PARTSTAT`, ...), if set.
+fn get_param<'a>(prop: &'a Property, key: &str) -> Option<&'a str> {
+    prop.params
+        .as_ref()?
+        .iter()
+        .find(|(k, _)| k == key)
+        .and_then(|(_, values)| values.first())
+        .map(String::as_str)
+}
+
+/// The `TZID` parameter without the double quotes and whitespace some
+/// providers wrap it in (`TZID="Europe/London"`).
+fn get_tzid(prop: &Property) -> Option<&str> {
+    get_param(prop, "TZID").map(|tzid| tzid.trim().trim_matches('"').trim())
+}
+
+/// Whether `email` is an ATTENDEE of the event with `PARTSTAT=DECLINED`. The
+/// value is usually `mailto:<address>`; both sides compare case-insensitively.
+fn declined_by(event: &IcalEvent, email: &str) -> bool {
+    event.properties.iter().filter(|p| p.name == "ATTENDEE").any(|p| {
+        let address = strip_mailto(p.value.as_deref().unwrap_or_default());
+        address.eq_ignore_ascii_case(email)
+            && get_param(p, "PARTSTAT").is_some_and(|status| status.eq_ignore_ascii_case("DECLINED"))
+    })
+}
+
+/// A calendar user address without its (case-insensitive) `mailto:` scheme.
+fn strip_mailto(value: &str) -> &str {
+    match value.get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("mailto:") => &value[7..],
+        _ => value,
+    }
+}
+
 fn get_property(event: &IcalEvent, name: &str) -> Option<String> {
     event
         .properties
@@ -406,46 +1179,141 @@ fn get_event_summary(event: &IcalEvent) -> Option<String> {
     get_property(event, "SUMMARY")
 }
 
-fn get_video_link(event: &IcalEvent) -> Option<String> {
-    // Check for X-GOOGLE-CONFERENCE property (Google Calendar)
-    if let Some(url) = get_property(event, "X-GOOGLE-CONFERENCE")
-        && url.starts_with("http")
-    {
-        return Some(url);
-    }
+/// The event's video call link (see [`find_video_link`]), with a Zoom
+/// passcode from the description added when the link lacks one.
+fn get_video_link(event: &IcalEvent, allowed_hosts: Option<&[String]>) -> Option<String> {
+    let link = find_video_link(event, allowed_hosts)?;
+    let description = get_property(event, "DESCRIPTION").unwrap_or_default();
+    Some(with_zoom_passcode(link, &description))
+}
 
-    // Check for URL property (Zoom, Teams, etc.)
-    if let Some(url) = get_property(event, "URL")
-        && url.starts_with("http")
-    {
-        return Some(url);
-    }
+/// The spoken reminder an event's description asks for with a line like
+/// `NEXTCALL-SAY: Time for the board call` (the prefix in any case); `None`
+/// without one, or if it's blank.
+fn say_override(description: &str) -> Option<String> {
+    description.lines().find_map(|line| {
+        let line = line.trim();
+        let prefix = line.get(..SAY_OVERRIDE_PREFIX.len())?;
+        let text = line[SAY_OVERRIDE_PREFIX.len()..].trim();
+        (prefix.eq_ignore_ascii_case(SAY_OVERRIDE_PREFIX) && !text.is_empty()).then(|| text.to_string())
+    })
+}
 
-    // Check location field
-    if let Some(location) = get_property(event, "LOCATION")
-        && location.starts_with("http")
-    {
-        return Some(location);
-    }
+/// Description line prefix for [`say_override`].
+const SAY_OVERRIDE_PREFIX: &str = "NEXTCALL-SAY:";
+
+/// The event's video call link: the first link to a known conferencing host
+/// ([`is_conferencing_link`]) from X-GOOGLE-CONFERENCE, URL, LOCATION or a
+/// DESCRIPTION line, in that order - so a generic URL (e.g. the calendar's
+/// own event page) never beats the Teams link in the body - else the first
+/// http(s) link from the properties. With `allowed_hosts`, only links whose
+/// host contains one of them are considered (description lines mentioning
+/// one are scanned too).
+fn find_video_link(event: &IcalEvent, allowed_hosts: Option<&[String]>) -> Option<String> {
+    // X-GOOGLE-CONFERENCE (Google Calendar), URL (Zoom, Teams, etc.), LOCATION
+    let mut candidates: Vec<String> = ["X-GOOGLE-CONFERENCE", "URL", "LOCATION"]
+        .into_iter()
+        .filter_map(|name| get_property(event, name))
+        .filter(|url| url.starts_with("http"))
+        .collect();
 
     // Check description for meeting links
     if let Some(description) = get_property(event, "DESCRIPTION") {
         // Look for common video conferencing URLs
         for line in description.lines() {
             // Check if line contains a video conferencing URL
-            if line.contains("zoom.us") || line.contains("meet.google.com") || line.contains("teams.microsoft.com") {
+            if line.contains("zoom.us")
+                || line.contains("meet.google.com")
+                || line.contains("teams.microsoft.com")
+                || allowed_hosts.is_some_and(|hosts| hosts.iter().any(|host| line.contains(host.as_str())))
+            {
                 // Extract the URL from the line
                 if let Some(start) = line.find("http") {
                     let url_part = &line[start..];
                     // Find the end of the URL (space, newline, or end of string)
                     let end = url_part.find(|c: char| c.is_whitespace()).unwrap_or(url_part.len());
-                    return Some(url_part[..end].to_string());
+                    candidates.push(url_part[..end].to_string());
                 }
             }
         }
     }
 
-    None
+    if let Some(hosts) = allowed_hosts {
+        candidates.retain(|url| host_allowed(url, hosts));
+    }
+    let best = candidates.iter().position(|url| is_conferencing_link(url)).unwrap_or(0);
+    (best < candidates.len()).then(|| candidates.swap_remove(best))
+}
+
+/// Whether `url` is on a conferencing host: Zoom, Google Meet or Teams.
+fn is_conferencing_link(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| {
+        url.host_str().is_some_and(|host| {
+            host == "zoom.us"
+                || host.ends_with(".zoom.us")
+                || host == "meet.google.com"
+                || host == "teams.microsoft.com"
+        })
+    })
+}
+
+/// Whether `url`'s host contains one of `hosts`.
+fn host_allowed(url: &str, hosts: &[String]) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| {
+        url.host_str()
+            .is_some_and(|host| hosts.iter().any(|allowed| host.contains(allowed.as_str())))
+    })
+}
+
+/// Adds `?pwd=` to a Zoom `/j/<id>` link that has none, so joining doesn't
+/// prompt for the passcode. Taken from another link to the same meeting in
+/// `description` that carries one, else a "Passcode: ..." (or "Password:")
+/// line; Zoom accepts the plain passcode as `pwd`. Other links, and Zoom
+/// links with no passcode to be found, are returned as they are.
+fn with_zoom_passcode(link: String, description: &str) -> String {
+    let Ok(mut url) = reqwest::Url::parse(&link) else {
+        return link;
+    };
+    let is_zoom = url
+        .host_str()
+        .is_some_and(|h| h == "zoom.us" || h.ends_with(".zoom.us"));
+    let Some(id) = url
+        .path()
+        .strip_prefix("/j/")
+        .map(|id| id.trim_end_matches('/').to_string())
+    else {
+        return link;
+    };
+    if !is_zoom || url.query_pairs().any(|(key, _)| key == "pwd") {
+        return link;
+    }
+    let from_link = description
+        .split_whitespace()
+        .filter(|word| word.contains(&format!("/j/{id}?")))
+        .filter_map(|word| reqwest::Url::parse(word.trim_end_matches(['>', ')', ','])).ok())
+        .find_map(|other| {
+            other
+                .query_pairs()
+                .find(|(key, _)| key == "pwd")
+                .map(|(_, pwd)| pwd.into_owned())
+        });
+    let from_line = || {
+        description.lines().find_map(|line| {
+            let (label, value) = line.split_once(':')?;
+            let label = label.trim().to_ascii_lowercase();
+            (label.ends_with("passcode") || label.ends_with("password"))
+                .then(|| value.split_whitespace().next())
+                .flatten()
+                .map(str::to_string)
+        })
+    };
+    match from_link.or_else(from_line) {
+        Some(pwd) => {
+            url.query_pairs_mut().append_pair("pwd", &pwd);
+            url.to_string()
+        }
+        None => link,
+    }
 }
 
 #[cfg(test)]
@@ -462,12 +1330,49 @@ mod tests {
     }
 
     fn parse(events: &str) -> Cal {
-        let calendar_feed = CalendarFeed {
-            url: String::new(),
-            candidates: parse_candidates(feed(events).as_bytes(), now()).unwrap(),
-            expires: Instant::now(),
+        parse_with(events, &Config::default())
+    }
+
+    /// Like [`parse`], as the user `my_email`.
+    fn parse_as(events: &str, my_email: Option<&str>) -> Cal {
+        let config = Config {
+            my_email: my_email.map(str::to_string),
+            ..Config::default()
         };
-        calendar_feed.cal(now())
+        parse_with(events, &config)
+    }
+
+    /// Like [`parse`], with the feed options from `config`.
+    fn parse_with(events: &str, config: &Config) -> Cal {
+        feed_with(events, config).cal(now())
+    }
+
+    /// A source answering every fetch with what its function returns.
+    struct MockSource(fn() -> Result<bytes::Bytes, CalendarError>);
+
+    impl CalendarSource for MockSource {
+        fn fetch_bytes(&self) -> Result<bytes::Bytes, CalendarError> {
+            (self.0)()
+        }
+    }
+
+    /// A feed primed with `events`, as if just fetched; its source fails.
+    fn feed_with(events: &str, config: &Config) -> CalendarFeed {
+        let options = Options::new(config);
+        let parsed = parse_candidates(feed(events).as_bytes(), now(), &options).unwrap();
+        CalendarFeed {
+            source: Box::new(MockSource(|| Err(CalendarError::NetworkError("no source".to_string())))),
+            candidates: parsed.candidates,
+            day_calls: parsed.day_calls,
+            options,
+            expires: Instant::now(),
+            disk_cache: None,
+            calendar_id: 0,
+            primed: true,
+            loaded: true,
+            failures: 0,
+            last_error: None,
+        }
     }
 
     fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
@@ -484,6 +1389,25 @@ mod tests {
         assert_eq!(cal.next_call.unwrap().start_time, utc(2026, 7, 9, 10, 0));
     }
 
+    #[test]
+    fn organizer_and_attendee_count() {
+        let cal = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:planning\n{LINK}\
+             ORGANIZER;CN=Alice:MAILTO:alice@example.com\n\
+             ATTENDEE;CN=Alice:mailto:alice@example.com\nATTENDEE:mailto:bob@example.com\n\
+             ATTENDEE:mailto:carol@example.com\nEND:VEVENT\n"
+        ));
+        let event = cal.next_call.unwrap();
+        assert_eq!(event.organizer.as_deref(), Some("alice@example.com"));
+        assert_eq!(event.attendee_count, Some(3));
+        // absent from a plain event
+        let cal = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:solo\n{LINK}END:VEVENT\n"
+        ));
+        let event = cal.next_call.unwrap();
+        assert_eq!((event.organizer, event.attendee_count), (None, None));
+    }
+
     #[test]
     fn summary_unescapes_rfc5545_text() {
         let cal = parse(&format!(
@@ -492,10 +1416,486 @@ mod tests {
         assert_eq!(cal.next_call.unwrap().summary, r"Bill, Samuel ; co\ two");
     }
 
+    #[test]
+    fn upcoming_in_start_order() {
+        let event = |start: &str, summary: &str| {
+            format!("BEGIN:VEVENT\nDTSTART:{start}\nSUMMARY:{summary}\n{LINK}END:VEVENT\n")
+        };
+        let events = [
+            event("20260709T150000Z", "third"),
+            event("20260709T100000Z", "first"),
+            event("20260709T120000Z", "second"),
+            event("20260708T100000Z", "yesterday"),
+        ]
+        .concat();
+        let calendar_feed = feed_with(&events, &Config::default());
+        let summaries = |limit| {
+            calendar_feed
+                .upcoming(now(), limit)
+                .into_iter()
+                .map(|e| e.summary)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summaries(5), ["first", "second", "third"]);
+        assert_eq!(summaries(2), ["first", "second"]);
+        assert_eq!(calendar_feed.cal(now()).next_call.unwrap().summary, "first");
+    }
+
+    #[test]
+    fn calendar_check() {
+        assert!(check_calendar(feed("").as_bytes()).is_ok());
+        assert!(check_calendar(b"<html><body>Sign in</body></html>").is_err());
+        assert!(check_calendar(b"").is_err());
+    }
+
+    #[test]
+    fn backoff_doubles_to_cap() {
+        let minutes: Vec<u64> = (1..=7).map(|n| backoff(n).as_secs() / 60).collect();
+        assert_eq!(minutes, [1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn floating_start_is_local_time() {
+        let content = feed("BEGIN:VEVENT\nDTSTART:20260709T100000\nSUMMARY:floating\nEND:VEVENT\n");
+        let calendar = IcalParser::new(BufReader::new(content.as_bytes()))
+            .next()
+            .unwrap()
+            .unwrap();
+        let naive = chrono::NaiveDate::from_ymd_opt(2026, 7, 9)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap();
+        let expected = Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(extract_datetime(&calendar.events[0], &Timezones::new()), Some(expected));
+    }
+
+    /// Serves one HTTP request on a local port with the raw response `head`
+    /// (status line and headers, without the blank line) and `body`,
+    /// returning the URL and a handle yielding the request received.
+    fn serve_once(head: &str, body: &'static [u8]) -> (String, std::thread::JoinHandle<String>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/calendar.ics", listener.local_addr().unwrap());
+        let head = format!("{head}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(body).unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn gzip_response_decoded() {
+        // sent gzip-encoded, like some corporate calendar servers do
+        let (url, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/calendar\r\nContent-Encoding: gzip",
+            include_bytes!("../fixtures/meeting.ics.gz"),
+        );
+        let client = http_client(&Config::default()).unwrap();
+        let content = fetch_bytes(&client, &url, None).unwrap();
+        server.join().unwrap();
+        let candidates = parse_candidates(&content, now(), &Options::new(&Config::default()))
+            .unwrap()
+            .candidates;
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].summary, "gzipped");
+    }
+
+    #[test]
+    fn bom_and_latin1_feeds_parse() {
+        let summaries = |content: &[u8]| {
+            check_calendar(content).unwrap();
+            parse_candidates(content, now(), &Options::new(&Config::default()))
+                .unwrap()
+                .candidates
+                .into_iter()
+                .map(|c| c.summary)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summaries(include_bytes!("../fixtures/bom.ics")), ["Café catch-up"]);
+        // the same feed re-encoded as Latin-1, without the BOM
+        let latin1: Vec<u8> = include_str!("../fixtures/bom.ics")
+            .trim_start_matches('\u{feff}')
+            .chars()
+            .map(|c| u8::try_from(c).unwrap())
+            .collect();
+        assert_eq!(summaries(&latin1), ["Café catch-up"]);
+    }
+
+    #[test]
+    fn ignore_patterns_match_whole_titles() {
+        assert!(glob_match("lunch", "lunch"));
+        assert!(!glob_match("lunch", "lunch with client"));
+        assert!(glob_match("focus time*", "focus time (recurring)"));
+        assert!(glob_match("*1:1*", "alice 1:1 bob"));
+        assert!(glob_match("sync ?", "sync a"));
+        assert!(!glob_match("sync ?", "sync"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+        let config = Config {
+            ignore_summary_patterns: Some(vec!["Lunch".to_string(), "focus TIME*".to_string()]),
+            ..Config::default()
+        };
+        let events = ["Lunch", "lunch ", "Lunch with client", "Focus time (deep work)"]
+            .map(|summary| {
+                format!("BEGIN:VEVENT\nUID:{summary}\nDTSTART:20260709T100000Z\nSUMMARY:{summary}\n{LINK}END:VEVENT\n")
+            })
+            .concat();
+        let summaries: Vec<_> = feed_with(&events, &config)
+            .upcoming(now(), 5)
+            .into_iter()
+            .map(|c| c.summary)
+            .collect();
+        assert_eq!(summaries, ["Lunch with client"]);
+    }
+
+    #[test]
+    fn calls_today_include_earlier_calls() {
+        let event = |uid: &str, start: &str| {
+            format!("BEGIN:VEVENT\nUID:{uid}\nDTSTART:{start}\nSUMMARY:{uid}\n{LINK}END:VEVENT\n")
+        };
+        // 06:00 is long past the lookback; the standup is in two merged
+        // calendars; tomorrow's call isn't today's
+        let events = [
+            event("early", "20260709T060000Z"),
+            event("standup", "20260709T100000Z"),
+            event("standup", "20260709T100000Z"),
+            event("tomorrow", "20260710T100000Z"),
+        ]
+        .concat();
+        let feed = feed_with(&events, &Config::default());
+        assert!(feed.candidates.iter().all(|c| c.summary != "early"));
+        // "today" is the local day, so in a far-off zone some fall outside it
+        let expected: Vec<_> = [utc(2026, 7, 9, 6, 0), utc(2026, 7, 9, 10, 0), utc(2026, 7, 10, 10, 0)]
+            .into_iter()
+            .filter(|start| local_date(*start) == local_date(now()))
+            .collect();
+        assert_eq!(feed.calls_today(now()), expected);
+        // one expansion of a daily 06:00 call gives both today's (for the
+        // tally) and the next ones (as candidates)
+        let events = "BEGIN:VEVENT\nUID:daily\nDTSTART:20260701T060000Z\nRRULE:FREQ=DAILY\n\
+                      SUMMARY:daily\nLOCATION:https://meet.google.com/abc-defg-hij\nEND:VEVENT\n";
+        let feed = feed_with(events, &Config::default());
+        let starts: Vec<_> = feed.candidates.iter().map(|c| c.start_time).collect();
+        assert_eq!(starts, [utc(2026, 7, 10, 6, 0), utc(2026, 7, 11, 6, 0)]);
+        let expected: Vec<_> = [utc(2026, 7, 8, 6, 0), utc(2026, 7, 9, 6, 0), utc(2026, 7, 10, 6, 0)]
+            .into_iter()
+            .filter(|start| local_date(*start) == local_date(now()))
+            .collect();
+        assert_eq!(feed.calls_today(now()), expected);
+    }
+
+    #[test]
+    fn simultaneous_calls_ordered_deterministically() {
+        let event = |summary: &str, link: &str| {
+            format!("BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:{summary}\n{link}END:VEVENT\n")
+        };
+        let zoom = "LOCATION:https://acme.zoom.us/j/1234567890\n";
+        // the unlinked event sorts first by summary but isn't a call at all
+        let linked = [event("Acme prep", ""), event("Zoom sync", zoom)].concat();
+        assert_eq!(parse(&linked).next_call.unwrap().summary, "Zoom sync");
+        // both linked: by summary, whatever the feed order
+        for events in [
+            [event("Standup", LINK), event("Board", zoom)],
+            [event("Board", zoom), event("Standup", LINK)],
+        ] {
+            let feed = feed_with(&events.concat(), &Config::default());
+            let summaries: Vec<_> = feed.upcoming(now(), 5).into_iter().map(|c| c.summary).collect();
+            assert_eq!(summaries, ["Board", "Standup"]);
+        }
+    }
+
+    #[test]
+    fn mixed_fixture_parsed() {
+        let config = Config {
+            my_email: Some("me@example.com".to_string()),
+            ..Config::default()
+        };
+        let parsed = parse_candidates(include_bytes!("../fixtures/mixed.ics"), now(), &Options::new(&config)).unwrap();
+        let at = |day, hour, minute| Utc.with_ymd_and_hms(2026, 7, day, hour, minute, 0).unwrap();
+        let candidates = &parsed.candidates;
+        // the declined pitch and the link-less lunch are dropped
+        let summaries: Vec<_> = candidates.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(
+            summaries,
+            ["Team offsite", "Zoom sync", "Meet review", "Teams planning"]
+        );
+        let starts: Vec<_> = candidates.iter().map(|c| c.start_time).collect();
+        // London noon is 11:00 UTC in July
+        assert_eq!(starts, [at(10, 0, 0), at(9, 10, 0), at(9, 11, 0), at(9, 13, 0)]);
+        assert_eq!(candidates[2].end_time, Some(at(9, 11, 30)));
+        let links: Vec<_> = candidates.iter().map(|c| c.video_link.as_str()).collect();
+        assert_eq!(
+            links,
+            [
+                "https://meet.google.com/off-site-day",
+                "https://acme.zoom.us/j/1234567890?pwd=abc",
+                "https://meet.google.com/abc-defg-hij",
+                "https://teams.microsoft.com/l/meetup-join/19%3ameeting_x/0",
+            ]
+        );
+        let app_links: Vec<_> = candidates.iter().map(|c| c.app_link.as_deref()).collect();
+        assert_eq!(
+            app_links,
+            [
+                None,
+                Some("zoommtg://acme.zoom.us/join?action=join&confno=1234567890&pwd=abc"),
+                None,
+                Some("msteams:/l/meetup-join/19%3ameeting_x/0"),
+            ]
+        );
+        assert!(candidates[0].all_day);
+        let feed = CalendarFeed {
+            candidates: parsed.candidates,
+            ..feed_with("", &config)
+        };
+        assert_eq!(feed.cal(now()).next_call.unwrap().summary, "Zoom sync");
+    }
+
+    #[test]
+    fn say_override_fixture_parsed() {
+        let parsed = parse_candidates(
+            include_bytes!("../fixtures/say_override.ics"),
+            now(),
+            &Options::new(&Config::default()),
+        )
+        .unwrap();
+        let overrides: Vec<_> = parsed.candidates.iter().map(|c| c.say_override.as_deref()).collect();
+        // unescaped and unfolded, prefix matched in any case; a blank one or
+        // one mid-line is no override
+        assert_eq!(
+            overrides,
+            [Some("Time for the board call, bring the numbers"), None, None]
+        );
+        // the notification still gets the real title
+        assert_eq!(parsed.candidates[0].summary, "Q3 board meeting (external)");
+    }
+
+    #[test]
+    fn all_day_events_skipped() {
+        let content = include_bytes!("../fixtures/all_day.ics");
+        let feed = |config: &Config| CalendarFeed {
+            candidates: parse_candidates(content, now(), &Options::new(config))
+                .unwrap()
+                .candidates,
+            ..feed_with("", config)
+        };
+        // the offsite "starts" at midnight, ahead of the standup
+        let next = feed(&Config::default()).cal(now()).next_call.unwrap();
+        assert_eq!(next.summary, "Standup");
+        assert!(!next.all_day);
+        let config = Config {
+            include_all_day: Some(true),
+            ..Config::default()
+        };
+        let next = feed(&config).cal(now()).next_call.unwrap();
+        assert_eq!(next.summary, "Team offsite");
+        assert!(next.all_day);
+    }
+
+    #[test]
+    fn user_agent_and_extra_headers_sent() {
+        let config = Config {
+            ical_headers: Some(HashMap::from([("X-Api-Token".to_string(), "abc123".to_string())])),
+            ..Config::default()
+        };
+        let (url, server) = serve_once("HTTP/1.1 200 OK", b"");
+        fetch_bytes(&http_client(&config).unwrap(), &url, None).unwrap();
+        let request = server.join().unwrap();
+        let user_agent = format!("user-agent: nextcall/{}\r\n", env!("CARGO_PKG_VERSION"));
+        assert!(request.contains(&user_agent), "{request}");
+        assert!(request.contains("x-api-token: abc123\r\n"), "{request}");
+    }
+
+    #[test]
+    fn requests_go_through_the_proxy_but_not_for_localhost() {
+        let (proxy, proxy_server) = serve_once("HTTP/1.1 200 OK", b"");
+        let config = Config {
+            proxy: Some(proxy.replace("/calendar.ics", "")),
+            ..Config::default()
+        };
+        let client = http_client(&config).unwrap();
+        fetch_bytes(&client, "http://calendar.example/feed.ics", None).unwrap();
+        // a proxy is sent the absolute URL
+        assert!(
+            proxy_server
+                .join()
+                .unwrap()
+                .starts_with("GET http://calendar.example/feed.ics ")
+        );
+        if std::env::var_os("NO_PROXY").is_none() && std::env::var_os("no_proxy").is_none() {
+            let (url, server) = serve_once("HTTP/1.1 200 OK", b"");
+            fetch_bytes(&client, &url, None).unwrap();
+            assert!(server.join().unwrap().starts_with("GET /calendar.ics "));
+        }
+    }
+
+    #[test]
+    fn redirects_followed_to_final_status() {
+        let client = http_client(&Config::default()).unwrap();
+        // a 302 to a (signed) URL serving the calendar: its body is what's read
+        let (target, target_server) = serve_once("HTTP/1.1 200 OK", b"BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n");
+        let (url, server) = serve_once(&format!("HTTP/1.1 302 Found\r\nLocation: {target}?sig=abc"), b"moved");
+        let body = fetch_bytes(&client, &url, None).unwrap();
+        server.join().unwrap();
+        assert!(target_server.join().unwrap().starts_with("GET /calendar.ics?sig=abc "));
+        assert_eq!(&body[..], b"BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n");
+        // a chain ending in an error reports the final status
+        let (target, target_server) = serve_once("HTTP/1.1 404 Not Found", b"expired");
+        let (url, server) = serve_once(&format!("HTTP/1.1 302 Found\r\nLocation: {target}"), b"");
+        let err = fetch_bytes(&client, &url, None).unwrap_err();
+        server.join().unwrap();
+        target_server.join().unwrap();
+        assert!(matches!(err, CalendarError::HttpStatus(_)));
+        assert!(err.to_string().starts_with("404"), "{err}");
+    }
+
+    #[test]
+    fn fixture_served_over_http() {
+        let config = Config {
+            my_email: Some("me@example.com".to_string()),
+            ..Config::default()
+        };
+        let source = |url| HttpSource {
+            url,
+            credentials: None,
+            client: http_client(&config).unwrap(),
+        };
+        let (url, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/calendar",
+            include_bytes!("../fixtures/mixed.ics"),
+        );
+        let parsed = fetch_candidates(&source(url), now(), &Options::new(&config)).unwrap();
+        server.join().unwrap();
+        assert_eq!(parsed.candidates.len(), 4);
+        // anything but a 2xx is an HttpStatus error, whatever the body
+        for status in ["404 Not Found", "500 Internal Server Error"] {
+            let (url, server) = serve_once(&format!("HTTP/1.1 {status}"), include_bytes!("../fixtures/mixed.ics"));
+            let err = fetch_candidates(&source(url), now(), &Options::new(&config)).unwrap_err();
+            server.join().unwrap();
+            assert!(matches!(err, CalendarError::HttpStatus(_)), "{err:?}");
+            assert!(err.to_string().starts_with(&status[..3]), "{err}");
+        }
+    }
+
+    #[test]
+    fn slow_server_times_out() {
+        let config = Config {
+            http_timeout_secs: Some(1),
+            ..Config::default()
+        };
+        let client = http_client(&config).unwrap();
+        // accepts the connection but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/calendar.ics", listener.local_addr().unwrap());
+        let started = Instant::now();
+        let err = fetch_bytes(&client, &url, None).unwrap_err();
+        assert!(matches!(err, CalendarError::NetworkError(_)), "{err:?}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+        drop(listener);
+    }
+
+    #[test]
+    fn failed_fetches_keep_the_last_calendar() {
+        let mut feed = feed_with(
+            &format!("BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:standup\n{LINK}END:VEVENT\n"),
+            &Config::default(),
+        );
+        let next = |feed: &CalendarFeed| feed.cal(now()).next_call.map(|e| e.summary);
+        // a server error, garbage and a refused connection each count as a
+        // failure, backing off further while the cached call stays
+        let failures: [MockSource; 3] = [
+            MockSource(|| Err(CalendarError::HttpStatus("500 Internal Server Error: oops".to_string()))),
+            MockSource(|| Ok(bytes::Bytes::from_static(b"<html>sign in</html>"))),
+            MockSource(|| Err(CalendarError::NetworkError("connection refused".to_string()))),
+        ];
+        for source in failures {
+            feed.source = Box::new(source);
+            feed.expire();
+            assert!(feed.fetch(now()));
+        }
+        assert_eq!(feed.failures, 3);
+        assert!(feed.expires > Instant::now() + backoff(2));
+        assert_eq!(next(&feed).as_deref(), Some("standup"));
+        assert!(feed.last_error.as_deref().unwrap().contains("connection refused"));
+        // a successful fetch replaces the calendar and resets the backoff
+        feed.source = Box::new(MockSource(|| {
+            Ok(bytes::Bytes::from_static(
+                b"BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n",
+            ))
+        }));
+        feed.expire();
+        assert!(!feed.fetch(now()));
+        assert_eq!(feed.failures, 0);
+        assert_eq!(feed.last_error, None);
+        assert_eq!(next(&feed), None);
+    }
+
+    #[test]
+    fn loaded_once_a_calendar_arrives() {
+        let mut feed = CalendarFeed {
+            primed: false,
+            loaded: false,
+            ..feed_with("", &Config::default())
+        };
+        // offline at launch with no disk cache: nothing to go on yet
+        assert!(feed.fetch(now()));
+        assert!(!feed.loaded());
+        feed.source = Box::new(MockSource(|| {
+            Ok(bytes::Bytes::from_static(
+                b"BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n",
+            ))
+        }));
+        feed.expire();
+        assert!(!feed.fetch(now()));
+        assert!(feed.loaded());
+    }
+
+    #[test]
+    fn basic_auth_sent_and_401_explained() {
+        let config = Config {
+            ical_username: Some("me".to_string()),
+            ical_password: Some("secret".to_string()),
+            ..Config::default()
+        };
+        let client = http_client(&config).unwrap();
+        let (url, server) = serve_once("HTTP/1.1 401 Unauthorized", b"");
+        let err = fetch_bytes(&client, &url, Credentials::new(&config).as_ref()).unwrap_err();
+        // base64("me:secret")
+        assert!(server.join().unwrap().contains("authorization: Basic bWU6c2VjcmV0\r\n"));
+        assert!(matches!(err, CalendarError::HttpStatus(_)));
+        assert!(err.to_string().contains("ical_username"), "{err}");
+        // credentials embedded in the URL are sent by reqwest itself
+        let (url, server) = serve_once("HTTP/1.1 200 OK", b"");
+        let url = url.replace("http://", "http://me:secret@");
+        fetch_bytes(&client, &url, None).unwrap();
+        assert!(server.join().unwrap().contains("authorization: Basic bWU6c2VjcmV0\r\n"));
+    }
+
     #[test]
     fn event_without_video_link_ignored() {
-        let cal = parse("BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:no link\nEND:VEVENT\n");
-        assert!(cal.next_call.is_none());
+        let events = "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:no link\nEND:VEVENT\n\
+                      BEGIN:VEVENT\nDTSTART:20260708T100000Z\nSUMMARY:yesterday\nEND:VEVENT\n";
+        assert!(parse(events).next_call.is_none());
+        // ...but counted, unlike past events, so the log shows the feed has content
+        let options = Options::new(&Config::default());
+        let parsed = parse_candidates(feed(events).as_bytes(), now(), &options).unwrap();
+        assert_eq!((parsed.events, parsed.upcoming_without_link), (2, 1));
     }
 
     #[test]
@@ -509,6 +1909,33 @@ mod tests {
         assert_eq!(next.summary, "standup");
     }
 
+    #[test]
+    fn weekly_recurring_event_started_two_weeks_ago() {
+        // weekly since Thursday June 25th: today's 10:00 occurrence is next
+        let cal = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART:20260625T100000Z\nRRULE:FREQ=WEEKLY\nSUMMARY:weekly\nUID:w@x\n{LINK}END:VEVENT\n"
+        ));
+        assert_eq!(cal.next_call.unwrap().start_time, utc(2026, 7, 9, 10, 0));
+    }
+
+    #[test]
+    fn weekly_rrule_interval_byday_count_until() {
+        let weekly = |rule: &str| {
+            parse(&format!(
+                "BEGIN:VEVENT\nDTSTART:20260625T100000Z\nRRULE:{rule}\nSUMMARY:weekly\nUID:w@x\n{LINK}END:VEVENT\n"
+            ))
+            .next_call
+            .map(|e| e.start_time)
+        };
+        // fortnightly from June 25th also lands on today
+        assert_eq!(weekly("FREQ=WEEKLY;INTERVAL=2"), Some(utc(2026, 7, 9, 10, 0)));
+        // Mondays and Fridays: tomorrow is the next occurrence
+        assert_eq!(weekly("FREQ=WEEKLY;BYDAY=MO,FR"), Some(utc(2026, 7, 10, 10, 0)));
+        // exhausted rules (June 25th and July 2nd only) yield nothing upcoming
+        assert_eq!(weekly("FREQ=WEEKLY;COUNT=2"), None);
+        assert_eq!(weekly("FREQ=WEEKLY;UNTIL=20260703T000000Z"), None);
+    }
+
     #[test]
     fn recurring_event_with_tzid() {
         // 11:00 Amsterdam == 09:00 UTC in July (CEST), weekdays only:
@@ -519,6 +1946,48 @@ mod tests {
         assert_eq!(cal.next_call.unwrap().start_time, utc(2026, 7, 9, 9, 0));
     }
 
+    #[test]
+    fn quoted_tzid() {
+        let cal = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART;TZID=\" Europe/London\":20260709T103000\nSUMMARY:london\n{LINK}END:VEVENT\n"
+        ));
+        assert_eq!(cal.next_call.unwrap().start_time, utc(2026, 7, 9, 9, 30));
+        let cal = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART;TZID=\"Europe/London\":20260708T103000\nRRULE:FREQ=DAILY\nSUMMARY:london daily\n\
+             UID:l@x\n{LINK}END:VEVENT\n"
+        ));
+        assert_eq!(cal.next_call.unwrap().start_time, utc(2026, 7, 9, 9, 30));
+    }
+
+    /// Outlook's export of Central European Time under a non-IANA name.
+    const CUSTOM_TZ: &str = "BEGIN:VTIMEZONE\nTZID:Customized Time Zone\n\
+        BEGIN:STANDARD\nDTSTART:16010101T030000\nTZOFFSETFROM:+0200\nTZOFFSETTO:+0100\n\
+        RRULE:FREQ=YEARLY;INTERVAL=1;BYDAY=-1SU;BYMONTH=10\nEND:STANDARD\n\
+        BEGIN:DAYLIGHT\nDTSTART:16010101T020000\nTZOFFSETFROM:+0100\nTZOFFSETTO:+0200\n\
+        RRULE:FREQ=YEARLY;INTERVAL=1;BYDAY=-1SU;BYMONTH=3\nEND:DAYLIGHT\nEND:VTIMEZONE\n";
+
+    #[test]
+    fn custom_tzid_from_vtimezone() {
+        let cal = parse(&format!(
+            "{CUSTOM_TZ}BEGIN:VEVENT\nDTSTART;TZID=Customized Time Zone:20260709T113000\n\
+             DTEND;TZID=Customized Time Zone:20260709T120000\nSUMMARY:outlook\n{LINK}END:VEVENT\n"
+        ));
+        let event = cal.next_call.unwrap();
+        assert_eq!(event.start_time, utc(2026, 7, 9, 9, 30));
+        assert_eq!(event.end_time, Some(utc(2026, 7, 9, 10, 0)));
+    }
+
+    #[test]
+    fn recurring_event_with_custom_tzid() {
+        // first occurrence in winter (+01:00); in July it is still 11:00
+        // local, i.e. 09:00 UTC
+        let cal = parse(&format!(
+            "{CUSTOM_TZ}BEGIN:VEVENT\nDTSTART;TZID=Customized Time Zone:20260105T110000\n\
+             RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR\nSUMMARY:outlook standup\nUID:o@x\n{LINK}END:VEVENT\n"
+        ));
+        assert_eq!(cal.next_call.unwrap().start_time, utc(2026, 7, 9, 9, 0));
+    }
+
     #[test]
     fn old_recurring_occurrence_skipped() {
         // started 68 minutes ago: too old for the next_call window, so the
@@ -557,6 +2026,51 @@ mod tests {
         assert!(cal.next_call.is_none());
     }
 
+    #[test]
+    fn cancelled_fixture_filtered() {
+        let candidates = |config: &Config| {
+            parse_candidates(
+                include_bytes!("../fixtures/cancelled.ics"),
+                now(),
+                &Options::new(config),
+            )
+            .unwrap()
+            .candidates
+            .into_iter()
+            .map(|c| c.summary)
+            .collect::<Vec<_>>()
+        };
+        // the cancelled Zoom call is dropped despite its link
+        assert_eq!(candidates(&Config::default()), ["Maybe: design review", "Standup"]);
+        let config = Config {
+            skip_tentative: Some(true),
+            ..Config::default()
+        };
+        assert_eq!(candidates(&config), ["Standup"]);
+    }
+
+    #[test]
+    fn declined_event_ignored_for_my_email() {
+        let event = |partstat: &str| {
+            format!(
+                "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:invite\n\
+                 ATTENDEE;CN=Other;PARTSTAT=ACCEPTED:mailto:other@example.com\n\
+                 ATTENDEE;CN=Me;PARTSTAT={partstat}:MAILTO:Me@Example.com\n{LINK}END:VEVENT\n"
+            )
+        };
+        // declined by me (address matched case-insensitively): skipped
+        assert!(parse_as(&event("DECLINED"), Some("me@example.com")).next_call.is_none());
+        // accepted by me: kept
+        assert!(parse_as(&event("ACCEPTED"), Some("me@example.com")).next_call.is_some());
+        // someone else's address, or no my_email at all: everything is kept
+        assert!(
+            parse_as(&event("DECLINED"), Some("other@example.com"))
+                .next_call
+                .is_some()
+        );
+        assert!(parse(&event("DECLINED")).next_call.is_some());
+    }
+
     #[test]
     fn started_event_ages_out_in_favour_of_later_one() {
         // meeting started 30 min ago plus one later today
@@ -567,6 +2081,236 @@ mod tests {
         assert_eq!(cal.next_call.unwrap().summary, "later");
     }
 
+    #[test]
+    fn ended_event_skipped_but_long_meeting_kept() {
+        // 5 minute standup that started 8 minutes ago: over, despite the grace window
+        let cal = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART:20260709T090000Z\nDTEND:20260709T090500Z\nSUMMARY:short\n{LINK}END:VEVENT\n"
+        ));
+        assert!(cal.next_call.is_none());
+        // an hour-long meeting that started 8 minutes ago is still joinable
+        let cal = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART:20260709T090000Z\nDTEND:20260709T100000Z\nSUMMARY:long\n{LINK}END:VEVENT\n"
+        ));
+        let next = cal.next_call.unwrap();
+        assert_eq!(next.summary, "long");
+        assert_eq!(next.end_time, Some(utc(2026, 7, 9, 10, 0)));
+    }
+
+    #[test]
+    fn duration_sets_end_of_each_occurrence() {
+        // daily 5 minute standup via DURATION: today's ended, tomorrow's is next
+        let cal = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART:20260701T090000Z\nDURATION:PT5M\nRRULE:FREQ=DAILY\nSUMMARY:standup\nUID:e@x\n{LINK}END:VEVENT\n"
+        ));
+        let next = cal.next_call.unwrap();
+        assert_eq!(next.start_time, utc(2026, 7, 10, 9, 0));
+        assert_eq!(next.end_time, Some(utc(2026, 7, 10, 9, 5)));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("PT30M"), Some(TimeDelta::minutes(30)));
+        assert_eq!(parse_duration("PT1H30M"), Some(TimeDelta::minutes(90)));
+        assert_eq!(parse_duration("P1DT2H"), Some(TimeDelta::hours(26)));
+        assert_eq!(parse_duration("P2W"), Some(TimeDelta::weeks(2)));
+        assert_eq!(parse_duration("-PT15M"), Some(TimeDelta::minutes(-15)));
+        assert_eq!(parse_duration("30M"), None);
+        assert_eq!(parse_duration("PT30"), None);
+        assert_eq!(parse_duration("P1H"), None);
+    }
+
+    #[test]
+    fn configurable_join_grace() {
+        // a workshop that started 38 minutes ago, running all afternoon
+        let workshop = format!(
+            "BEGIN:VEVENT\nDTSTART:20260709T083000Z\nDTEND:20260709T170000Z\nSUMMARY:workshop\n{LINK}END:VEVENT\n"
+        );
+        // past the default 10 minute grace: no longer next
+        assert!(parse(&workshop).next_call.is_none());
+        // a 60 minute grace keeps it joinable
+        let config = Config {
+            join_grace_minutes: Some(60),
+            ..Config::default()
+        };
+        assert_eq!(parse_with(&workshop, &config).next_call.unwrap().summary, "workshop");
+    }
+
+    #[test]
+    fn long_join_grace_still_finds_the_next_occurrence() {
+        // a daily call, today's already over; with a day's grace yesterday's
+        // and today's fill the lookback, but tomorrow's must still come next
+        let daily = format!(
+            "BEGIN:VEVENT\nUID:daily\nDTSTART:20260701T083000Z\nDTEND:20260701T084500Z\n\
+             RRULE:FREQ=DAILY\nSUMMARY:daily\n{LINK}END:VEVENT\n"
+        );
+        let config = Config {
+            join_grace_minutes: Some(1440),
+            ..Config::default()
+        };
+        let next = parse_with(&daily, &config).next_call.unwrap();
+        assert_eq!(next.start_time, utc(2026, 7, 10, 8, 30));
+    }
+
+    #[test]
+    fn conferencing_link_beats_generic_url() {
+        let cal = parse(
+            "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:teams\n\
+             URL:https://outlook.office365.com/owa/?itemid=abc\n\
+             DESCRIPTION:Join on your computer\\nhttps://teams.microsoft.com/l/meetup-join/19%3ameeting_x/0\\n\n\
+             END:VEVENT\n",
+        );
+        assert_eq!(
+            cal.next_call.unwrap().video_link,
+            "https://teams.microsoft.com/l/meetup-join/19%3ameeting_x/0"
+        );
+        // with no conferencing link anywhere, the first http(s) link still counts
+        let cal =
+            parse("BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:other\nURL:https://whereby.com/room\nEND:VEVENT\n");
+        assert_eq!(cal.next_call.unwrap().video_link, "https://whereby.com/room");
+    }
+
+    #[test]
+    fn merged_calendars_deduplicated() {
+        let shared = "BEGIN:VEVENT\nUID:planning@x\nDTSTART:20260709T100000Z\nSUMMARY:planning\n\
+             LOCATION:https://meet.google.com/abc-defg-hij\nEND:VEVENT\n";
+        let no_uid = format!("BEGIN:VEVENT\nDTSTART:20260709T110000Z\nSUMMARY:retro\n{LINK}END:VEVENT\n");
+        // a personal and a shared calendar, both with the invite
+        let content = format!(
+            "{}{}",
+            feed(&format!("{shared}{no_uid}")),
+            feed(&format!("{shared}{no_uid}"))
+        );
+        let candidates = parse_candidates(content.as_bytes(), now(), &Options::new(&Config::default()))
+            .unwrap()
+            .candidates;
+        let summaries: Vec<&str> = candidates.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, ["planning", "retro"]);
+    }
+
+    #[test]
+    fn video_link_hosts_allowlist() {
+        let config = Config {
+            video_link_hosts: Some(vec!["zoom.us".to_string(), "teams.microsoft.com".to_string()]),
+            ..Config::default()
+        };
+        let sharepoint = "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:planning\n\
+             LOCATION:https://acme.sharepoint.com/sites/team/Shared%20Documents/plan.docx\nEND:VEVENT\n";
+        // without the allowlist the SharePoint link is taken as a video link
+        assert!(parse(sharepoint).next_call.is_some());
+        assert!(parse_with(sharepoint, &config).next_call.is_none());
+        let teams = "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:teams\n\
+             URL:https://acme.sharepoint.com/sites/team\n\
+             DESCRIPTION:Join: https://teams.microsoft.com/l/meetup-join/19%3ameeting_x/0\n\nEND:VEVENT\n";
+        assert_eq!(
+            parse_with(teams, &config).next_call.unwrap().video_link,
+            "https://teams.microsoft.com/l/meetup-join/19%3ameeting_x/0"
+        );
+    }
+
+    #[test]
+    fn zoom_passcode_from_description() {
+        let zoom = |description: &str| {
+            let cal = parse(&format!(
+                "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:zoom\nLOCATION:https://acme.zoom.us/j/1234567890\n\
+                 DESCRIPTION:{description}\nEND:VEVENT\n"
+            ));
+            cal.next_call.unwrap().video_link
+        };
+        assert_eq!(
+            zoom("Join Zoom Meeting\\nMeeting ID: 123 456 7890\\nPasscode: 4f9Xk2\\n"),
+            "https://acme.zoom.us/j/1234567890?pwd=4f9Xk2"
+        );
+        assert_eq!(
+            zoom("One tap: https://acme.zoom.us/j/1234567890?pwd=aBcD.1 (desktop)\\nPasscode: 4f9Xk2"),
+            "https://acme.zoom.us/j/1234567890?pwd=aBcD.1"
+        );
+        // another meeting's link doesn't count; no passcode leaves the link alone
+        assert_eq!(
+            zoom("Prep: https://acme.zoom.us/j/999?pwd=other"),
+            "https://acme.zoom.us/j/1234567890"
+        );
+        assert_eq!(
+            with_zoom_passcode("https://zoom.us/j/1?pwd=abc".to_string(), "Passcode: 999"),
+            "https://zoom.us/j/1?pwd=abc"
+        );
+        assert_eq!(
+            with_zoom_passcode("https://meet.google.com/abc-defg-hij".to_string(), "Password: 999"),
+            "https://meet.google.com/abc-defg-hij"
+        );
+    }
+
+    #[test]
+    fn launch_urls() {
+        assert_eq!(
+            to_launch_url("https://us02web.zoom.us/j/81234567890?pwd=aBcD123.1").as_deref(),
+            Some("zoommtg://us02web.zoom.us/join?action=join&confno=81234567890&pwd=aBcD123.1")
+        );
+        assert_eq!(
+            to_launch_url("https://zoom.us/j/81234567890").as_deref(),
+            Some("zoommtg://zoom.us/join?action=join&confno=81234567890")
+        );
+        // a passcode that needs escaping stays one intact query value
+        assert_eq!(
+            to_launch_url("https://zoom.us/j/81234567890?pwd=a%20b%2Bc%26d%23e%25f").as_deref(),
+            Some("zoommtg://zoom.us/join?action=join&confno=81234567890&pwd=a+b%2Bc%26d%23e%25f")
+        );
+        let passcode = with_zoom_passcode("https://zoom.us/j/81234567890".to_string(), "Passcode: x&y#1+%");
+        let launch = reqwest::Url::parse(&to_launch_url(&passcode).unwrap()).unwrap();
+        assert_eq!(launch.query_pairs().find(|(key, _)| key == "pwd").unwrap().1, "x&y#1+%");
+        assert_eq!(
+            to_launch_url("https://teams.microsoft.com/l/meetup-join/19%3ameeting_abc%40thread.v2/0?context=%7b%7d")
+                .as_deref(),
+            Some("msteams:/l/meetup-join/19%3ameeting_abc%40thread.v2/0?context=%7b%7d")
+        );
+        // anything else (or an unrecognised Zoom path) keeps the https link
+        assert_eq!(to_launch_url("https://meet.google.com/abc-defg-hij"), None);
+        assert_eq!(to_launch_url("https://zoom.us/my/someone"), None);
+        assert_eq!(to_launch_url("not a url"), None);
+    }
+
+    #[test]
+    fn webcal_urls_normalized() {
+        let normalize = |url| normalize_url(url).map_err(|e| e.to_string());
+        assert_eq!(
+            normalize("webcal://p01-caldav.icloud.com/published/2/abc"),
+            Ok("https://p01-caldav.icloud.com/published/2/abc".to_string())
+        );
+        assert_eq!(
+            normalize("WEBCALS://x.com/c.ics"),
+            Ok("https://x.com/c.ics".to_string())
+        );
+        assert_eq!(normalize(" http://x.com/c.ics "), Ok("http://x.com/c.ics".to_string()));
+        assert!(normalize("ftp://x.com/c.ics").unwrap_err().contains("unsupported"));
+        assert!(normalize("x.com/c.ics").unwrap_err().contains("invalid"));
+    }
+
+    #[test]
+    fn disk_cache_round_trip_and_expiry() {
+        let path = std::env::temp_dir().join(format!("nextcall-cache-test-{}.json", std::process::id()));
+        let candidates = parse(&format!(
+            "BEGIN:VEVENT\nDTSTART:20260709T100000Z\nSUMMARY:cached\n{LINK}END:VEVENT\n"
+        ))
+        .next_call
+        .into_iter()
+        .collect::<Vec<_>>();
+        let calendar = url_fingerprint("https://example.com/calendar.ics");
+        save_disk_cache(&path, calendar, &candidates, now()).unwrap();
+        // fresh enough: restored as-is
+        assert_eq!(
+            load_disk_cache(&path, calendar, now() + TimeDelta::hours(23)),
+            Some(candidates)
+        );
+        // a day old: ignored
+        assert_eq!(load_disk_cache(&path, calendar, now() + TimeDelta::hours(24)), None);
+        // another calendar's: ignored, e.g. after the URL changed offline
+        let other = url_fingerprint("https://example.com/other.ics");
+        assert_eq!(load_disk_cache(&path, other, now()), None);
+        fs::remove_file(&path).unwrap();
+        // missing: ignored
+        assert_eq!(load_disk_cache(&path, calendar, now()), None);
+    }
+
     #[test]
     fn next_call_window_boundary() {
         // started exactly 10 minutes ago: still next_call
@@ -1,26 +1,94 @@
 //! The pure per-tick decision logic: given the calendar state, the current
-//! time, the previous tick's time and camera state, [`step`] decides what the
+//! time, the previous tick's time and whether the user is in a call (camera
+//! or microphone on, see [`crate::camera::in_call`]), [`step`] decides what the
 //! tray shows, whether an alert is due, and how long to sleep. Keeping it
 //! pure (no clock, no IO) makes the whole alerting behaviour unit-testable.
 
-use crate::ical::{Cal, NEXT_MAX_AGE_MINUTES, NextEvent};
-use crate::{notifications, say};
+use crate::config::Config;
+use crate::ical::{Cal, NEXT_MAX_AGE_MINUTES, NextEvent, local_date};
+use crate::notifications::{self, Interruption};
+use crate::tray::Urgency;
+use crate::{focus, say};
 use chrono::{DateTime, TimeDelta, Timelike, Utc};
 use std::{borrow::Cow, time::Duration};
 use tracing::info;
 
-/// Idle sleep cap: how long the loop may sleep with nothing coming up.
+/// Default idle sleep cap (`check_interval_secs`): how long the loop may
+/// sleep with nothing coming up.
 pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(180);
 
+/// The furthest-out call shown as an hours label ("9h"); beyond it, "...".
+pub const HOURS_LABEL_MAX: i64 = 9;
+
+/// Calls starting within this many minutes show the countdown as
+/// [`Urgency::Soon`].
+pub const SOON_MINUTES: i64 = 5;
+
+/// Alerting and polling preferences from the config, resolved each tick.
+#[derive(Debug)]
+pub struct Settings {
+    /// Minutes before the start at which a single heads-up alert fires.
+    pub notify_before_minutes: Option<i64>,
+    /// Once-a-minute reminders after the start alert (start + 1..=n minutes).
+    pub post_start_reminders: i64,
+    /// The idle sleep cap: `check_interval_secs`, or on battery
+    /// `battery_check_interval_secs`.
+    pub check_interval: Duration,
+}
+
+impl Settings {
+    /// The settings from `config`, using the battery interval when
+    /// `on_battery`.
+    pub fn new(config: &Config, on_battery: bool) -> Self {
+        let interval_secs = if on_battery {
+            config.battery_check_interval_secs.or(config.check_interval_secs)
+        } else {
+            config.check_interval_secs
+        };
+        Self {
+            check_interval: interval_secs.map_or(DEFAULT_CHECK_INTERVAL, Duration::from_secs),
+            notify_before_minutes: config.notify_before_minutes.filter(|n| *n > 0).map(i64::from),
+            post_start_reminders: config
+                .post_start_reminders
+                .map_or(Self::default().post_start_reminders, i64::from),
+        }
+    }
+
+    /// Whether the alert instant `minutes` after start is within the alert
+    /// window: the start alert (0) always is, then the configured reminders.
+    fn alerts_at(&self, minutes: i64) -> bool {
+        (0..=self.post_start_reminders).contains(&minutes)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            notify_before_minutes: None,
+            post_start_reminders: NEXT_MAX_AGE_MINUTES - 1,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+}
+
 /// The outcome of one [`step`]: everything the main loop must apply.
 #[derive(Debug)]
 pub struct Step {
     /// Menu bar text: minutes until the next call ("5") or since its start
-    /// ("-2"), or "..." when nothing is within the countdown hour.
+    /// ("-2"), whole hours ("3h") up to [`HOURS_LABEL_MAX`] away, else "...".
     pub title: Cow<'static, str>,
+    /// Colour cue for the title.
+    pub urgency: Urgency,
+    /// How far through the last hour before the next call we are, 0.0 to
+    /// 1.0 (full at the start), for the tray's optional progress ring;
+    /// `None` while no countdown is shown.
+    pub progress: Option<f64>,
     /// Text for the status line at the top of the tray menu.
     pub status: String,
-    /// An alert due this tick: the event and whole minutes since its start.
+    /// Hover text for the menu bar item: the next call and how far off it is.
+    pub tooltip: String,
+    /// An alert due this tick: the event and whole minutes since its start
+    /// (negative for the heads-up before it).
     pub alert: Option<(NextEvent, i64)>,
     /// How long to sleep until the next tick.
     pub sleep: Duration,
@@ -29,74 +97,181 @@ pub struct Step {
 /// Pure per-tick decision. `prev_tick` is the previous invocation's `now`;
 /// an alert fires iff its scheduled instant lies in `(prev_tick, now]` -
 /// every instant belongs to exactly one tick, so alerts fire exactly once
-/// without any dedup state. `dismissed` is the start time of a call the user
-/// muted via the tray's "Dismiss" item: all its alerts are suppressed.
+/// without any dedup state. `muted` is the start time of a call whose alerts
+/// are all suppressed: dismissed via the tray's "Dismiss" item, or already in
+/// progress at launch (see [`in_progress`]). `joined` is the start
+/// time of a call the user has joined (see [`joined`]): muted too, and its
+/// countdown gives way to the idle "...".
 pub fn step(
     cal: &Cal,
     now: DateTime<Utc>,
     prev_tick: DateTime<Utc>,
-    camera_active: bool,
-    dismissed: Option<DateTime<Utc>>,
+    in_call: bool,
+    muted: Option<DateTime<Utc>>,
+    joined: Option<DateTime<Utc>>,
+    settings: &Settings,
 ) -> Step {
+    let joined = cal.next_call.as_ref().filter(|e| joined == Some(e.start_time));
     Step {
-        title: tray_title(cal, now),
-        status: status_line(cal, now),
-        alert: pending_alert(cal, now, prev_tick, camera_active, dismissed),
-        sleep: sleep_duration(cal, now),
+        title: match joined {
+            Some(_) => "...".into(),
+            None => tray_title(cal, now),
+        },
+        urgency: match joined {
+            Some(_) => Urgency::Normal,
+            None => urgency(cal, now),
+        },
+        progress: match joined {
+            Some(_) => None,
+            None => progress(cal, now),
+        },
+        status: match joined {
+            Some(event) => format!("In call: {}", event.summary),
+            None => status_line(cal, now),
+        },
+        tooltip: match joined {
+            Some(event) => format!("In call: {}", event.summary),
+            None => tooltip(cal, now),
+        },
+        alert: pending_alert(
+            cal,
+            now,
+            prev_tick,
+            in_call,
+            muted.or(joined.map(|e| e.start_time)),
+            settings,
+        ),
+        sleep: sleep_duration(cal, now, settings),
     }
 }
 
-/// Sends the notification (and camera-gated speech) for an alert produced by
-/// [`step`]. `minutes` is whole minutes since the event started. Not part of
-/// `step` so the decision stays pure; may block for seconds while speaking.
-pub fn fire_alert(event: &NextEvent, minutes: i64, camera_active: bool, eleven_labs_key: Option<&str>) {
+/// The start time of `next_call` if it has already started at `now`. Main
+/// records this on the first tick: a call in progress at launch gets a single
+/// alert with its real age (none with `quiet_launch`) and is then muted,
+/// unlike calls that start while running.
+pub fn in_progress(cal: &Cal, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    cal.next_call
+        .as_ref()
+        .map(|e| e.start_time)
+        .filter(|start| *start <= now)
+}
+
+/// The start time of `next_call` if the user has evidently joined it: it is in
+/// progress and the user was in a call (camera or microphone on) at this tick
+/// and the previous one (a single reading could be another app briefly
+/// grabbing the device). Main keeps the result until the call changes, so the
+/// camera going off mid-call doesn't restart the reminders.
+pub fn joined(cal: &Cal, now: DateTime<Utc>, in_call: bool, prev_in_call: bool) -> Option<DateTime<Utc>> {
+    in_progress(cal, now).filter(|_| in_call && prev_in_call)
+}
+
+/// The start time of `next_call` if the user pressed a notification's Snooze
+/// button for it (`snooze`: the call's start as a unix timestamp, and when it
+/// was pressed) less than `minutes` ago. Like a dismissal, it is matched
+/// against the call that is still next, so a snooze never mutes another call.
+pub fn snoozed(
+    cal: &Cal,
+    now: DateTime<Utc>,
+    snooze: Option<(i64, DateTime<Utc>)>,
+    minutes: i64,
+) -> Option<DateTime<Utc>> {
+    let (start_ts, at) = snooze?;
+    let start = cal.next_call.as_ref()?.start_time;
+    (start.timestamp() == start_ts && now < at + TimeDelta::minutes(minutes)).then_some(start)
+}
+
+/// Sends the notification (and speech, unless the user is in a call or, with
+/// `respect_focus`, a Focus is) for an alert produced by [`step`]. `minutes`
+/// is whole minutes since the event started (negative for the heads-up); a
+/// `silenced` call gets a silent banner and no speech. Not part of `step` so
+/// the decision stays pure; speech plays in the background.
+pub fn fire_alert(event: &NextEvent, minutes: i64, in_call: bool, silenced: bool, config: &Config) {
     info!(
-        "alerting for {:?}, {minutes} minutes after start, camera active: {camera_active}",
+        "alerting for {:?}, {minutes} minutes after start, in call: {in_call}, silenced: {silenced}",
         event.summary
     );
     let started_description: Cow<'static, str> = match minutes {
+        -1 => "starts in one minute".into(),
+        ..0 => format!("starts in {} minutes", -minutes).into(),
         0 => "is starting now".into(),
         1 => "started one minute ago, join it now!".into(),
         _ => format!("started {minutes} minutes ago, join it now!").into(),
     };
+    // prefer the native app; the web link is the fallback if nothing handles it
     notifications::send(
         "Nextcall",
-        Some(&format!("Call {started_description}")),
-        &event.summary,
-        Some(&event.video_link),
+        // the scheduled time too: "3 minutes ago" alone is unclear after being away
+        Some(&format!(
+            "Call at {} {started_description}",
+            local_time_string(event.start_time)
+        )),
+        &notification_body(event),
+        Some(event.app_link.as_deref().unwrap_or(&event.video_link)),
+        event.app_link.as_ref().map(|_| event.video_link.as_str()),
+        Some(event.start_time),
+        if silenced {
+            Interruption::Silent
+        } else {
+            Interruption::Sound
+        },
     );
-    if !camera_active {
-        let summary = say::tts_friendly(sayevent_summary(event));
-        let message = format!(r#"Your call "{summary}" {started_description}"#);
-        let _ = say::say(&message, eleven_labs_key);
+    if silenced || !speaks(minutes, config) {
+        return;
+    }
+    let focused = config.respect_focus.unwrap_or(true) && focus::focus_active();
+    if focused {
+        info!("Focus is on, not speaking");
+    }
+    if !in_call && !focused {
+        let message = spoken_message(event, &started_description);
+        // a prompt joiner doesn't need the start announcement
+        let delay = if minutes == 0 {
+            Duration::from_secs(config.say_delay_secs.unwrap_or(0))
+        } else {
+            Duration::ZERO
+        };
+        say::say(&message, delay, config);
     }
 }
 
-/// The alert whose scheduled instant (start + k minutes, k = 0..10) lies in
-/// `(prev_tick, now]`, if any. Only the latest such instant fires (a tick
-/// covering several missed instants alerts once); nags after the start alert
-/// stop once the user is on the call, and a dismissed call never alerts.
+/// Whether an alert `minutes` after the start is spoken at all: `speak`
+/// gates every announcement, `speak_before` just the heads-up.
+fn speaks(minutes: i64, config: &Config) -> bool {
+    config.speak.unwrap_or(true) && (minutes >= 0 || config.speak_before.unwrap_or(true))
+}
+
+/// The alert whose scheduled instant (start + k minutes for k within
+/// [`Settings::alerts_at`], or the
+/// heads-up at start - `notify_before_minutes`) lies in `(prev_tick, now]`, if
+/// any. Only the latest such instant fires (a tick covering several missed
+/// instants alerts once); nags after the start alert stop once the user is on
+/// the call, and a muted call never alerts.
 fn pending_alert(
     cal: &Cal,
     now: DateTime<Utc>,
     prev_tick: DateTime<Utc>,
-    camera_active: bool,
-    dismissed: Option<DateTime<Utc>>,
+    in_call: bool,
+    muted: Option<DateTime<Utc>>,
+    settings: &Settings,
 ) -> Option<(NextEvent, i64)> {
     let event = cal.next_call.as_ref()?;
-    if dismissed == Some(event.start_time) {
-        // user hit "Dismiss" for this call: mute its whole alert window
+    if muted == Some(event.start_time) {
+        // dismissed, or in progress at a quiet launch: mute its whole alert window
         return None;
     }
     let since_start = now.signed_duration_since(event.start_time);
     if since_start < TimeDelta::zero() {
         // not started: num_minutes() truncates toward zero, so a tick in the
-        // final minute before start would otherwise fire the start alert early
-        return None;
+        // final minute before start would otherwise fire the start alert early;
+        // only the heads-up can be due. Like the start alert it always
+        // notifies, with speech camera-gated by fire_alert
+        let before = settings.notify_before_minutes?;
+        let instant = event.start_time - TimeDelta::minutes(before);
+        return (prev_tick < instant && instant <= now).then(|| (event.clone(), -before));
     }
     // whole minutes since start = k of the latest alert instant at or before now
     let minutes = since_start.num_minutes();
-    if minutes >= NEXT_MAX_AGE_MINUTES {
+    if !settings.alerts_at(minutes) {
         return None;
     }
     let instant = event.start_time + TimeDelta::minutes(minutes);
@@ -104,7 +279,7 @@ fn pending_alert(
         // a previous tick already covered this instant
         return None;
     }
-    if minutes >= 1 && camera_active {
+    if minutes >= 1 && in_call {
         // user is on the call: stop nagging (the start alert always notifies)
         return None;
     }
@@ -112,8 +287,9 @@ fn pending_alert(
 }
 
 /// The menu bar text: minutes until the next call (rounded to the nearest
-/// minute) while it is within an hour, or whole minutes since it started
-/// (negative, truncated - matching "started N minutes ago"); else "...".
+/// minute) while it is within an hour, whole hours until it ("2h", truncated)
+/// up to [`HOURS_LABEL_MAX`], or whole minutes since it started (negative,
+/// truncated - matching "started N minutes ago"); else "...".
 fn tray_title(cal: &Cal, now: DateTime<Utc>) -> Cow<'static, str> {
     let until_start = cal
         .next_call
@@ -128,41 +304,165 @@ fn tray_title(cal: &Cal, now: DateTime<Utc>) -> Cow<'static, str> {
         // elapsed minutes truncate (a call 1m59s in "started 1 minute ago",
         // so "-1"); formatted by hand so the first minute shows "-0"
         Some(until) if until < TimeDelta::zero() => format!("-{}", (-until).num_minutes()).into(),
+        // truncated, so "1h" covers 1h00m..1h59m: the label never overstates
+        Some(until) if until.num_hours() <= HOURS_LABEL_MAX => format!("{}h", until.num_hours()).into(),
         _ => "...".into(),
     }
 }
 
+/// The countdown colour: red once the call has started, orange within
+/// [`SOON_MINUTES`] of the start, else normal.
+fn urgency(cal: &Cal, now: DateTime<Utc>) -> Urgency {
+    match cal.next_call {
+        Some(ref event) if event.start_time <= now => Urgency::Started,
+        Some(ref event) if event.start_time - now <= TimeDelta::minutes(SOON_MINUTES) => Urgency::Soon,
+        _ => Urgency::Normal,
+    }
+}
+
+/// The tray's hover text: the next call's summary and local start, with how
+/// long until it ("in 1h 5m") or since it started ("started 3m ago").
+fn tooltip(cal: &Cal, now: DateTime<Utc>) -> String {
+    let Some(event) = &cal.next_call else {
+        return "No upcoming calls".to_string();
+    };
+    let span = |d: TimeDelta| match (d.num_hours(), d.num_minutes() % 60) {
+        (0, m) => format!("{m}m"),
+        (h, m) => format!("{h}h {m}m"),
+    };
+    let until = event.start_time.signed_duration_since(now);
+    let when = if until >= TimeDelta::zero() {
+        // rounded up, so a call 30s away reads "in 1m", not "in 0m"
+        format!("in {}", span(until + TimeDelta::seconds(59)))
+    } else {
+        format!("started {} ago", span(-until))
+    };
+    format!("{} at {} — {when}", event.summary, local_time_string(event.start_time))
+}
+
+/// The progress ring's fill: the fraction of the hour before the start that
+/// has passed while the minute countdown shows, full once the call started.
+fn progress(cal: &Cal, now: DateTime<Utc>) -> Option<f64> {
+    let until = cal.next_call.as_ref()?.start_time.signed_duration_since(now);
+    if until <= TimeDelta::zero() {
+        return Some(1.0);
+    }
+    let hour = TimeDelta::hours(1).as_seconds_f64();
+    (until <= TimeDelta::hours(1)).then(|| 1.0 - until.as_seconds_f64() / hour)
+}
+
 /// One-line summary of the calendar state, shown at the top of the tray menu.
 fn status_line(cal: &Cal, now: DateTime<Utc>) -> String {
-    let local_start = |e: &NextEvent| e.start_time.with_timezone(&chrono::Local).format("%H:%M");
     match cal.next_call {
         Some(ref event) if event.start_time <= now => {
-            format!("In progress: {} (started {})", event.summary, local_start(event))
+            format!(
+                "In progress: {} (started {})",
+                event.summary,
+                local_time_string(event.start_time)
+            )
         }
-        Some(ref event) => format!("Next: {} at {}", event.summary, local_start(event)),
+        Some(ref event) => format!("Next: {} at {}", event.summary, local_time_string(event.start_time)),
         None => "No upcoming calls".to_string(),
     }
 }
 
+/// The launch confirmation's text: the [`status_line`], or for a call on
+/// another day, that day too ("Next: Standup on Fri at 09:30").
+pub fn startup_message(cal: &Cal, now: DateTime<Utc>) -> String {
+    match cal.next_call {
+        Some(ref event) if local_date(event.start_time) != local_date(now) => format!(
+            "No more calls today. Next: {} on {} at {}",
+            notification_summary(&event.summary),
+            local_day_string(event.start_time),
+            local_time_string(event.start_time)
+        ),
+        _ => status_line(cal, now),
+    }
+}
+
+/// The notification text: the summary (see [`notification_summary`]), plus
+/// the head count for a meeting with others ("Planning with 6 people").
+fn notification_body(event: &NextEvent) -> Cow<'_, str> {
+    let summary = notification_summary(&event.summary);
+    match event.attendee_count {
+        Some(count) if count >= 2 => format!("{summary} with {count} people").into(),
+        _ => summary,
+    }
+}
+
+/// Longest summary shown in a notification before it is cut with "…".
+const NOTIFICATION_SUMMARY_CHARS: usize = 60;
+
+/// The summary for a notification: without a "Call:" / "Call -" prefix (the
+/// notification says it's a call already), and cut at a word break to
+/// [`NOTIFICATION_SUMMARY_CHARS`] with an ellipsis, rather than wherever
+/// macOS runs out of room.
+fn notification_summary(summary: &str) -> Cow<'_, str> {
+    let trimmed = summary.trim();
+    let summary = istrip(trimmed, "call")
+        .trim_start()
+        .strip_prefix([':', '-'])
+        .map_or(trimmed, str::trim_start);
+    if summary.chars().count() <= NOTIFICATION_SUMMARY_CHARS {
+        return summary.into();
+    }
+    let cut: String = summary.chars().take(NOTIFICATION_SUMMARY_CHARS).collect();
+    // back to the last word break, unless that would lose most of it
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > cut.len() / 2 => &cut[..space],
+        _ => &cut,
+    };
+    format!("{}…", cut.trim_end_matches([' ', ',', '-', ':', '|'])).into()
+}
+
+/// `dt` as wall-clock time on the user's clock, e.g. "10:05": the one place
+/// an absolute time is converted for display.
+pub fn local_time_string(dt: DateTime<Utc>) -> String {
+    dt.with_timezone(&chrono::Local).format("%H:%M").to_string()
+}
+
+/// The weekday `dt` falls on by the user's clock, e.g. "Fri": for a time on
+/// another day, beside [`local_time_string`].
+pub fn local_day_string(dt: DateTime<Utc>) -> String {
+    dt.with_timezone(&chrono::Local).format("%a").to_string()
+}
+
+/// The date `dt` falls on by the user's clock, with its weekday, e.g.
+/// "Fri 2026-07-10".
+pub fn local_date_string(dt: DateTime<Utc>) -> String {
+    dt.with_timezone(&chrono::Local).format("%a %Y-%m-%d").to_string()
+}
+
 /// How long to sleep until the next instant the loop must act on: the next
-/// alert instant, the start (or start - 1h, when the countdown appears), or
-/// the next top-of-minute countdown tick. No post-alert adjustment is needed:
-/// an instant already covered by `(prev_tick, now]` can never fire again.
-fn sleep_duration(cal: &Cal, now: DateTime<Utc>) -> Duration {
-    let mut sleep = DEFAULT_CHECK_INTERVAL;
+/// alert instant (including the heads-up), the start, the next whole hour
+/// before it (when the hours label changes, or the minute countdown appears),
+/// or the next top-of-minute countdown tick. No post-alert
+/// adjustment is needed: an instant already covered by `(prev_tick, now]` can
+/// never fire again.
+fn sleep_duration(cal: &Cal, now: DateTime<Utc>, settings: &Settings) -> Duration {
+    let mut sleep = settings.check_interval;
     if let Some(ref event) = cal.next_call {
         let until_start = event.start_time.signed_duration_since(now);
         if until_start > TimeDelta::zero() {
-            // upcoming: wake at start, at start - 1h ("..." -> countdown), and
-            // at each top-of-minute while the countdown is showing
+            // upcoming: wake at start, at each whole hour before it ("2h" ->
+            // "1h" -> countdown), and at each top-of-minute while the
+            // countdown is showing
             let mut until = until_start;
-            let hour_before = until_start - TimeDelta::hours(1);
-            if hour_before > TimeDelta::zero() {
-                until = until.min(hour_before);
+            if until_start > TimeDelta::hours(1) {
+                let next_hour = until_start - TimeDelta::hours(until_start.num_hours());
+                until = until.min(next_hour);
             } else {
-                let top_of_minute = TimeDelta::seconds(i64::from(60 - now.second()));
+                // to the millisecond, so the minute flips right on :00
+                let top_of_minute = TimeDelta::seconds(i64::from(60 - now.second()))
+                    - TimeDelta::nanoseconds(i64::from(now.nanosecond()));
                 until = until.min(top_of_minute);
             }
+            if let Some(before) = settings.notify_before_minutes {
+                let until_heads_up = until_start - TimeDelta::minutes(before);
+                if until_heads_up > TimeDelta::zero() {
+                    until = until.min(until_heads_up);
+                }
+            }
             sleep = sleep.min(until.to_std().unwrap_or(Duration::ZERO));
         } else {
             // started: wake at the next minute boundary from start (the next
@@ -178,6 +478,16 @@ fn sleep_duration(cal: &Cal, now: DateTime<Utc>) -> Duration {
     sleep.max(Duration::from_secs(1))
 }
 
+/// What an alert says: the event's `say_override` if it has one, else e.g.
+/// `Your call "standup" is starting now`.
+fn spoken_message(event: &NextEvent, started_description: &str) -> String {
+    if let Some(text) = &event.say_override {
+        return text.clone();
+    }
+    let summary = say::tts_friendly(sayevent_summary(event));
+    format!(r#"Your call "{summary}" {started_description}"#)
+}
+
 /// Left strip `call` and `-` from the event summary
 fn sayevent_summary(event: &NextEvent) -> &str {
     let mut summary = event.summary.as_str().trim_start();
@@ -188,10 +498,9 @@ fn sayevent_summary(event: &NextEvent) -> &str {
 }
 
 fn istrip<'a>(s: &'a str, prefix: &str) -> &'a str {
-    if s.len() >= prefix.len() && s[..prefix.len()].eq_ignore_ascii_case(prefix) {
-        &s[prefix.len()..]
-    } else {
-        s
+    match s.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => &s[prefix.len()..],
+        _ => s,
     }
 }
 
@@ -209,8 +518,14 @@ mod tests {
     fn event(minutes_from_now: i64) -> NextEvent {
         NextEvent {
             start_time: now() + TimeDelta::minutes(minutes_from_now),
+            end_time: None,
             summary: "standup".to_string(),
             video_link: "https://meet.google.com/abc".to_string(),
+            app_link: None,
+            organizer: None,
+            attendee_count: None,
+            all_day: false,
+            say_override: None,
         }
     }
 
@@ -224,6 +539,24 @@ mod tests {
         TimeDelta::seconds(s)
     }
 
+    /// [`super::step`] with default settings and no joined call.
+    fn step(
+        cal: &Cal,
+        now: DateTime<Utc>,
+        prev_tick: DateTime<Utc>,
+        in_call: bool,
+        muted: Option<DateTime<Utc>>,
+    ) -> Step {
+        super::step(cal, now, prev_tick, in_call, muted, None, &Settings::default())
+    }
+
+    fn notify_before(minutes: i64) -> Settings {
+        Settings {
+            notify_before_minutes: Some(minutes),
+            ..Settings::default()
+        }
+    }
+
     #[test]
     fn alert_fires_when_instant_crossed() {
         // event started exactly at `now`; prev tick 5s earlier
@@ -247,7 +580,7 @@ mod tests {
 
     #[test]
     fn late_tick_still_fires_crossed_instant() {
-        // say-blocked: tick arrives 20s after the +1 minute instant
+        // delayed (e.g. a slow fetch): tick arrives 20s after the +1 minute instant
         let step = step(&cal(-1), now() + secs(20), now() - secs(45), false, None);
         assert_eq!(step.alert.as_ref().unwrap().1, 1);
     }
@@ -291,6 +624,63 @@ mod tests {
         assert!(other.alert.is_some(), "other call's dismissal ignored");
     }
 
+    #[test]
+    fn quiet_launch_mutes_only_the_call_in_progress() {
+        // launched 2 minutes into a call: it is recorded as in progress...
+        let launch = in_progress(&cal(-2), now());
+        assert_eq!(launch, Some(event(-2).start_time));
+        // ...so its nags stay silent while the countdown keeps running
+        let nag = step(&cal(-2), now() + secs(62), now() + secs(2), false, launch);
+        assert!(nag.alert.is_none(), "in-progress call muted");
+        assert_eq!(nag.title, "-3");
+        // a call that starts after launch alerts normally
+        let next = step(&cal(0), now(), now() - secs(5), false, launch);
+        assert_eq!(next.alert.as_ref().unwrap().1, 0);
+    }
+
+    #[test]
+    fn snooze_mutes_the_call_until_it_expires() {
+        let start = event(-2).start_time;
+        let snooze = Some((start.timestamp(), now() - TimeDelta::minutes(1)));
+        assert_eq!(snoozed(&cal(-2), now(), snooze, 5), Some(start));
+        // expired: reminders resume
+        assert_eq!(snoozed(&cal(-2), now() + TimeDelta::minutes(4), snooze, 5), None);
+        // a snooze for some other call does not mute this one
+        assert_eq!(snoozed(&cal(0), now(), snooze, 5), None);
+        assert_eq!(snoozed(&cal(-2), now(), None, 5), None);
+    }
+
+    #[test]
+    fn joined_after_two_camera_ticks() {
+        // one camera reading isn't enough; two in a row during the call is
+        assert_eq!(joined(&cal(-2), now(), true, false), None);
+        assert_eq!(joined(&cal(-2), now(), true, true), Some(event(-2).start_time));
+        // the camera on before the call starts (a previous call) doesn't count
+        assert_eq!(joined(&cal(5), now(), true, true), None);
+    }
+
+    #[test]
+    fn joined_call_stops_nagging_and_resets_title() {
+        let settings = Settings::default();
+        let start = Some(event(-3).start_time);
+        // even with the camera now off, the joined call stays quiet
+        let s = super::step(&cal(-3), now(), now() - secs(5), false, None, start, &settings);
+        assert!(s.alert.is_none());
+        assert_eq!(s.title, "...");
+        assert_eq!(s.status, "In call: standup");
+        // a joined call that is no longer next doesn't affect the new one
+        let s = super::step(&cal(0), now(), now() - secs(5), false, None, start, &settings);
+        assert_eq!(s.alert.as_ref().unwrap().1, 0);
+        assert_eq!(s.title, "0");
+    }
+
+    #[test]
+    fn upcoming_call_is_not_in_progress() {
+        // launched before the call starts: nothing to mute, it alerts as usual
+        assert_eq!(in_progress(&cal(5), now()), None);
+        assert_eq!(in_progress(&Cal::default(), now()), None);
+    }
+
     #[test]
     fn no_alert_before_start() {
         // tick 30s before start (the start - 60s top-of-minute wake):
@@ -305,6 +695,52 @@ mod tests {
         assert!(step.alert.is_none());
     }
 
+    #[test]
+    fn heads_up_fires_once_before_start() {
+        let settings = notify_before(5);
+        // the tick crossing start - 5m fires a single heads-up...
+        let heads_up = super::step(&cal(5), now(), now() - secs(30), false, None, None, &settings);
+        assert_eq!(heads_up.alert.as_ref().unwrap().1, -5);
+        // ...later ticks before the start don't repeat it
+        let later = super::step(&cal(5), now() + secs(60), now(), false, None, None, &settings);
+        assert!(later.alert.is_none());
+        // the start alert still fires as usual
+        let start = super::step(&cal(0), now(), now() - secs(5), false, None, None, &settings);
+        assert_eq!(start.alert.as_ref().unwrap().1, 0);
+        // camera only gates speech, like the start alert; muting silences it
+        let camera = super::step(&cal(5), now(), now() - secs(30), true, None, None, &settings);
+        assert_eq!(camera.alert.as_ref().unwrap().1, -5);
+        let muted = super::step(
+            &cal(5),
+            now(),
+            now() - secs(30),
+            false,
+            Some(event(5).start_time),
+            None,
+            &settings,
+        );
+        assert!(muted.alert.is_none());
+        // off by default
+        assert!(step(&cal(5), now(), now() - secs(30), false, None).alert.is_none());
+    }
+
+    #[test]
+    fn heads_up_wakes_the_loop() {
+        // 90 min out with a 75 min heads-up: wake at the heads-up, not start - 1h
+        let c = cal(90);
+        let sleep = super::step(&c, now(), now(), false, None, None, &notify_before(75)).sleep;
+        assert_eq!(sleep, DEFAULT_CHECK_INTERVAL.min(Duration::from_secs(15 * 60)));
+        let c = Cal {
+            next_call: Some(NextEvent {
+                start_time: now() + secs(5 * 60 + 20),
+                ..event(0)
+            }),
+        };
+        // countdown showing: the heads-up at 20s beats the top-of-minute tick
+        let sleep = super::step(&c, now(), now(), false, None, None, &notify_before(5)).sleep;
+        assert_eq!(sleep, Duration::from_secs(20));
+    }
+
     #[test]
     fn no_alerts_after_window() {
         // +10 minutes: outside the 0..10 alert window
@@ -312,10 +748,56 @@ mod tests {
         assert!(step.alert.is_none());
     }
 
+    #[test]
+    fn reminder_count_bounds_the_window() {
+        let reminders = |n| Settings {
+            post_start_reminders: n,
+            ..Settings::default()
+        };
+        // default: the start alert plus 9 reminders, matching the grace window
+        assert!(Settings::default().alerts_at(9));
+        assert!(!Settings::default().alerts_at(10));
+        assert!(reminders(3).alerts_at(3));
+        assert!(!reminders(3).alerts_at(4));
+        // 0: the start alert only
+        assert!(reminders(0).alerts_at(0));
+        assert!(!reminders(0).alerts_at(1));
+        let nag = super::step(&cal(-1), now(), now() - secs(5), false, None, None, &reminders(0));
+        assert!(nag.alert.is_none());
+    }
+
+    #[test]
+    fn startup_message_names_the_day_of_a_later_call() {
+        // local noon, so an hour either way stays on the same day
+        let noon = chrono::Local
+            .with_ymd_and_hms(2026, 7, 9, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let at = |start_time| Cal {
+            next_call: Some(NextEvent { start_time, ..event(0) }),
+        };
+        let later = noon + TimeDelta::hours(1);
+        assert_eq!(
+            startup_message(&at(later), noon),
+            format!("Next: standup at {}", local_time_string(later))
+        );
+        // 2026-07-11 is a Saturday
+        let saturday = noon + TimeDelta::days(2);
+        assert_eq!(
+            startup_message(&at(saturday), noon),
+            format!(
+                "No more calls today. Next: standup on Sat at {}",
+                local_time_string(saturday)
+            )
+        );
+        assert_eq!(local_date_string(saturday), "Sat 2026-07-11");
+        assert_eq!(startup_message(&Cal::default(), noon), "No upcoming calls");
+    }
+
     #[test]
     fn display_states() {
         assert_eq!(step(&Cal::default(), now(), now(), false, None).title, "...");
-        assert_eq!(step(&cal(90), now(), now(), false, None).title, "...");
+        assert_eq!(step(&cal(90), now(), now(), false, None).title, "1h");
         // rounds to the nearest minute: 58m40s away shows "59"...
         let c = Cal {
             next_call: Some(NextEvent {
@@ -360,6 +842,157 @@ mod tests {
         assert_eq!(step(&cal(30), now(), now(), true, None).title, "30");
     }
 
+    #[test]
+    fn urgency_levels() {
+        assert_eq!(
+            step(&Cal::default(), now(), now(), false, None).urgency,
+            Urgency::Normal
+        );
+        assert_eq!(step(&cal(20), now(), now(), false, None).urgency, Urgency::Normal);
+        assert_eq!(step(&cal(5), now(), now(), false, None).urgency, Urgency::Soon);
+        assert_eq!(step(&cal(0), now(), now(), false, None).urgency, Urgency::Started);
+        assert_eq!(step(&cal(-3), now(), now(), false, None).urgency, Urgency::Started);
+        // a joined call is no longer urgent
+        let joined = super::step(
+            &cal(-3),
+            now(),
+            now(),
+            false,
+            None,
+            Some(event(-3).start_time),
+            &Settings::default(),
+        );
+        assert_eq!(joined.urgency, Urgency::Normal);
+    }
+
+    #[test]
+    fn progress_fills_over_the_last_hour() {
+        let progress = |minutes| step(&cal(minutes), now(), now(), false, None).progress;
+        assert_eq!(progress(90), None);
+        assert_eq!(progress(60), Some(0.0));
+        assert_eq!(progress(15), Some(0.75));
+        assert_eq!(progress(0), Some(1.0));
+        assert_eq!(progress(-3), Some(1.0));
+        assert_eq!(step(&Cal::default(), now(), now(), false, None).progress, None);
+    }
+
+    #[test]
+    fn notification_body_counts_people() {
+        let with = |attendee_count| NextEvent {
+            attendee_count,
+            ..event(0)
+        };
+        assert_eq!(notification_body(&with(None)), "standup");
+        assert_eq!(notification_body(&with(Some(1))), "standup");
+        assert_eq!(notification_body(&with(Some(6))), "standup with 6 people");
+    }
+
+    #[test]
+    fn say_override_replaces_the_spoken_message() {
+        assert_eq!(
+            spoken_message(&event(0), "is starting now"),
+            r#"Your call "standup" is starting now"#
+        );
+        let custom = NextEvent {
+            say_override: Some("Time for the board call".to_string()),
+            ..event(0)
+        };
+        assert_eq!(spoken_message(&custom, "is starting now"), "Time for the board call");
+    }
+
+    #[test]
+    fn speak_before_gates_only_the_heads_up() {
+        let config = |speak, speak_before| Config {
+            speak,
+            speak_before,
+            ..Config::default()
+        };
+        assert!(speaks(-5, &config(None, None)));
+        assert!(!speaks(-5, &config(None, Some(false))));
+        assert!(speaks(0, &config(None, Some(false))));
+        assert!(speaks(3, &config(None, Some(false))));
+        assert!(!speaks(-5, &config(Some(false), Some(true))));
+        assert!(!speaks(0, &config(Some(false), None)));
+    }
+
+    #[test]
+    fn notification_summary_trimmed() {
+        assert_eq!(notification_summary("Daily Standup"), "Daily Standup");
+        assert_eq!(notification_summary("Call: Acme sync"), "Acme sync");
+        assert_eq!(notification_summary("call - Acme sync"), "Acme sync");
+        // "Callback" is not a "Call" prefix
+        assert_eq!(notification_summary("Callback review"), "Callback review");
+        assert_eq!(
+            notification_summary(
+                "Quarterly business review with the Acme Corporation leadership team, finance and legal"
+            ),
+            "Quarterly business review with the Acme Corporation…"
+        );
+        // no word break to back up to: cut mid-word
+        let long = "x".repeat(80);
+        assert_eq!(notification_summary(&long), format!("{}…", "x".repeat(60)));
+        // multi-byte characters are counted, not bytes
+        assert_eq!(notification_summary("Café Über"), "Café Über");
+    }
+
+    #[test]
+    fn tooltip_says_how_far_off() {
+        let tooltip = |minutes| step(&cal(minutes), now(), now(), false, None).tooltip;
+        let at = |minutes| local_time_string(event(minutes).start_time);
+        assert_eq!(tooltip(12), format!("standup at {} — in 12m", at(12)));
+        assert_eq!(tooltip(65), format!("standup at {} — in 1h 5m", at(65)));
+        assert_eq!(tooltip(-3), format!("standup at {} — started 3m ago", at(-3)));
+        let soon = step(&cal(1), now() + secs(30), now(), false, None).tooltip;
+        assert!(soon.ends_with("in 1m"), "{soon}");
+        assert_eq!(
+            step(&Cal::default(), now(), now(), false, None).tooltip,
+            "No upcoming calls"
+        );
+    }
+
+    #[test]
+    fn hours_labels() {
+        assert_eq!(step(&cal(59), now(), now(), false, None).title, "59");
+        assert_eq!(step(&cal(60), now(), now(), false, None).title, "60");
+        assert_eq!(step(&cal(61), now(), now(), false, None).title, "1h");
+        assert_eq!(step(&cal(125), now(), now(), false, None).title, "2h");
+        assert_eq!(step(&cal(9 * 60 + 59), now(), now(), false, None).title, "9h");
+        assert_eq!(step(&cal(10 * 60), now(), now(), false, None).title, "...");
+        // 2h05m away: wake as the label drops to "1h" (within the idle cap)
+        let c = Cal {
+            next_call: Some(NextEvent {
+                start_time: now() + TimeDelta::hours(2) + secs(100),
+                ..event(0)
+            }),
+        };
+        assert_eq!(step(&c, now(), now(), false, None).sleep, Duration::from_secs(100));
+    }
+
+    #[test]
+    fn check_interval_follows_power_source() {
+        let config = Config {
+            check_interval_secs: Some(60),
+            battery_check_interval_secs: Some(600),
+            ..Config::default()
+        };
+        assert_eq!(Settings::new(&config, false).check_interval, Duration::from_secs(60));
+        assert_eq!(Settings::new(&config, true).check_interval, Duration::from_secs(600));
+        // no battery interval: the one interval applies on either
+        let config = Config {
+            check_interval_secs: Some(60),
+            ..Config::default()
+        };
+        assert_eq!(Settings::new(&config, true).check_interval, Duration::from_secs(60));
+        assert_eq!(
+            Settings::new(&Config::default(), true).check_interval,
+            DEFAULT_CHECK_INTERVAL
+        );
+        // nothing upcoming: the loop sleeps the whole interval
+        let settings = Settings::new(&config, false);
+        let idle = super::step(&Cal::default(), now(), now(), false, None, None, &settings);
+        assert_eq!(idle.sleep, Duration::from_secs(60));
+    }
+
     #[test]
     fn sleep_durations() {
         // nothing upcoming: idle cap
@@ -375,6 +1008,9 @@ mod tests {
             }),
         };
         assert_eq!(step(&c, now(), now(), false, None).sleep, Duration::from_secs(60));
+        // a tick landing mid-second still wakes exactly on the minute
+        let late = now() + TimeDelta::milliseconds(2_750);
+        assert_eq!(step(&c, late, late, false, None).sleep, Duration::from_millis(57_250));
         // 30s to start: wake exactly at start
         let c = Cal {
             next_call: Some(NextEvent {
@@ -1,46 +1,105 @@
+mod browser;
 mod camera;
+mod cli;
 mod config;
+mod focus;
+mod history;
 mod ical;
 mod logic;
+mod mic;
+mod network;
 mod notifications;
+mod power;
 mod say;
 mod tray;
+mod vtimezone;
+mod wake;
 
 use anyhow::Result as AnyhowResult;
 use chrono::{DateTime, TimeDelta, Utc};
-use std::fs::OpenOptions;
-use std::thread::sleep;
+use notifications::Interruption;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use tracing::{error, info};
 use tracing_subscriber::fmt::time::ChronoLocal;
+use wake::Wake;
 
 /// Log timestamp format: RFC 3339 local time at whole-second precision —
 /// chrono has no 1-digit fraction specifier, and microseconds are noise here.
 const LOG_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%:z";
 
-/// Sets up tracing to a per-process temp file and stderr, returning the log
-/// path so the tray menu's "View Log" item can open it. A fresh file per run
-/// (pid in the name) means old logs never get clobbered mid-read.
+/// Default `max_log_bytes`: the log file's size cap before it is rotated.
+const DEFAULT_MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// The log size cap, from the config once loaded (logging starts first).
+static MAX_LOG_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_MAX_LOG_BYTES);
+
+/// The log file: `~/Library/Logs/nextcall.log`, where Console.app finds it
+/// (the temp dir without a home).
+#[cfg(target_os = "macos")]
+fn log_path() -> PathBuf {
+    match config::home() {
+        Ok(home) => PathBuf::from(home).join("Library").join("Logs").join("nextcall.log"),
+        Err(_) => std::env::temp_dir().join("nextcall.log"),
+    }
+}
+
+/// The log file: `$XDG_STATE_HOME/nextcall/nextcall.log`, with
+/// `~/.local/state` for an unset (or relative) `XDG_STATE_HOME`, as the XDG
+/// base directory spec has it (the temp dir without either).
+#[cfg(not(target_os = "macos"))]
+fn log_path() -> PathBuf {
+    let state = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            config::home()
+                .ok()
+                .map(|home| PathBuf::from(home).join(".local").join("state"))
+        });
+    state.map_or_else(
+        || std::env::temp_dir().join("nextcall.log"),
+        |dir| dir.join("nextcall").join("nextcall.log"),
+    )
+}
+
+/// Once `path` has reached `max_bytes`, moves it to `<path>.1` (replacing
+/// any older backup) so logging starts a new file.
+fn rotate_log(path: &Path, max_bytes: u64) {
+    if fs::metadata(path).is_ok_and(|m| m.len() >= max_bytes) {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".1");
+        let _ = fs::rename(path, backup);
+    }
+}
+
+/// Sets up tracing to a log file and stderr, returning the log path so the
+/// tray menu's "View Log" item can open it. Runs append to the same file, so
+/// the session where something went wrong survives a relaunch; it is rotated
+/// to one `.1` backup at `max_log_bytes`. The level comes from `NEXTCALL_LOG`,
+/// else `RUST_LOG` (`EnvFilter` directives, e.g. "debug"), else info.
 fn init_logging() -> AnyhowResult<String> {
-    let log_path = std::env::temp_dir()
-        .join(format!("nextcall-{}.log", std::process::id()))
-        .to_string_lossy()
-        .into_owned();
+    let path = log_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    rotate_log(&path, DEFAULT_MAX_LOG_BYTES);
+    let log_path = path.to_string_lossy().into_owned();
 
     // Create the file up front so "View Log" works before the first entry
-    let _log_file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(&log_path)?;
+    let _log_file = OpenOptions::new().create(true).append(true).open(&log_path)?;
 
     // Set up tracing subscriber with both file and stderr output
-    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::EnvFilter;
     use tracing_subscriber::layer::SubscriberExt;
     use tracing_subscriber::util::SubscriberInitExt;
 
     let writer_path = log_path.clone();
     let file_layer = tracing_subscriber::fmt::layer()
         .with_writer(move || {
+            rotate_log(Path::new(&writer_path), MAX_LOG_BYTES.load(Ordering::Relaxed));
             OpenOptions::new()
                 .create(true)
                 .append(true)
@@ -60,7 +119,11 @@ fn init_logging() -> AnyhowResult<String> {
     tracing_subscriber::registry()
         .with(file_layer)
         .with(stderr_layer)
-        .with(LevelFilter::INFO)
+        .with(
+            EnvFilter::try_from_env("NEXTCALL_LOG")
+                .or_else(|_| EnvFilter::try_from_default_env())
+                .unwrap_or_else(|_| EnvFilter::new("info")),
+        )
         .init();
 
     Ok(log_path)
@@ -69,7 +132,15 @@ fn init_logging() -> AnyhowResult<String> {
 /// Logs a fatal error, surfaces it as a notification, and exits.
 fn fatal(subtitle: &str, message: &str) -> ! {
     error!("Fatal error: {message}");
-    notifications::send("Nextcall Configuration", Some(subtitle), message, None);
+    notifications::send(
+        "Nextcall Configuration",
+        Some(subtitle),
+        message,
+        None,
+        None,
+        None,
+        Interruption::Sound,
+    );
     std::process::exit(1);
 }
 
@@ -79,8 +150,15 @@ fn main() {
         Err(e) => eprintln!("Failed to initialize logging: {}", e),
     }
 
+    // one-shot terminal modes (e.g. --check) exit here, before any UI
+    if let Some(code) = cli::run(std::env::args().skip(1)) {
+        std::process::exit(code);
+    }
+
     info!("Nextcall starting up");
 
+    // before any Join can be recorded, which would be overwritten
+    history::load();
     notifications::startup();
 
     let config = match config::get_config() {
@@ -91,15 +169,29 @@ fn main() {
                 "Create ~/nextcall.toml to configure Nextcall",
             );
         }
-        Err(err) => fatal("ERROR", &err.to_string()),
+        Err(err) => fatal("ERROR", &format!("{err:#}")),
     };
 
     info!("Configuration loaded: {config}");
+    MAX_LOG_BYTES.store(config.max_log_bytes.unwrap_or(DEFAULT_MAX_LOG_BYTES), Ordering::Relaxed);
+    notifications::set_snooze_minutes(config.snooze_minutes.unwrap_or(notifications::DEFAULT_SNOOZE_MINUTES));
+    browser::set_command(config.browser_command.clone());
+    tray::set_preferences(&config::Preferences::new(&config));
+    tray::set_about(config::get_config_path().ok().flatten().as_deref());
+
+    if config.respect_focus.unwrap_or(true) {
+        focus::startup();
+    }
 
     // Calendar polling and alerting run off the main thread so the AppKit run
     // loop below is never blocked by network requests.
+    let wakes = wake::channel();
+    if config.refresh_on_reconnect.unwrap_or(true) {
+        network::watch();
+    }
+    config::watch();
     std::thread::spawn(move || {
-        if let Err(err) = background(config) {
+        if let Err(err) = background(config, &wakes) {
             fatal("ERROR", &err.to_string());
         }
     });
@@ -112,17 +204,42 @@ fn main() {
 /// latency never delays an alert firing at its exact instant.
 const FETCH_LEAD: TimeDelta = TimeDelta::seconds(20);
 
+/// How many calls the tray's "Upcoming" submenu lists.
+const UPCOMING_MENU_LEN: usize = 5;
+
 /// The main loop: almost stateless. Each tick asks the feed for the calendar
-/// (cached, network at most once per TTL), reads the camera and the tray's
-/// dismiss toggle, lets the pure [`logic::step`] decide display/alert/sleep,
-/// applies the side effects, and sleeps. The only state: the feed's cache,
-/// the previous tick's timestamp (alerts exactly-once) and a log-only var.
-fn background(config: config::Config) -> AnyhowResult<()> {
-    let mut feed = ical::CalendarFeed::new(config.ical_url);
+/// (cached, network at most once per TTL), reads the camera/mic, the tray's
+/// dismiss toggle and the notifications' snooze, lets the pure
+/// [`logic::step`] decide display/alert/sleep, applies the side effects, and
+/// sleeps - waking early on a [`Wake`]. The only state: the feed's cache, the
+/// previous tick's timestamp (alerts exactly-once), the call in progress at a
+/// quiet launch, the joined call (with the previous camera/mic reading), and
+/// change-detection vars for the log and the "Upcoming" menu.
+fn background(mut config: config::Config, wakes: &Receiver<Wake>) -> AnyhowResult<()> {
+    let mut feed = ical::CalendarFeed::new(&config)?;
     let mut prev_tick = Utc::now();
     let mut scheduled = Utc::now();
     // Previous tick's dismissal, kept only to log transitions.
     let mut prev_dismissed: Option<DateTime<Utc>> = None;
+    // Likewise the previous tick's snooze.
+    let mut prev_snoozed: Option<DateTime<Utc>> = None;
+    // And the pause toggle.
+    let mut prev_paused = false;
+    // And the "Silence This Call" toggle.
+    let mut prev_silenced = false;
+    // With `quiet_launch`, the call already in progress on the first tick with
+    // a calendar is muted; `None` until that tick has run.
+    let mut launch_call: Option<Option<DateTime<Utc>>> = None;
+    // The call the user has joined (camera/mic on two ticks running), kept until
+    // the call changes so a camera blip off doesn't restart the reminders.
+    let mut joined: Option<DateTime<Utc>> = None;
+    let mut prev_in_call = false;
+    // Whether the launch confirmation is done with: sent, or turned off.
+    let mut startup_confirmed = !config.startup_notification.unwrap_or(true);
+    // The tray's "Upcoming" list, kept to rebuild the submenu only on change.
+    let mut prev_upcoming: Vec<ical::NextEvent> = Vec::new();
+    // Likewise the attendance line in "About Nextcall".
+    let mut prev_attendance = String::new();
 
     loop {
         // warm the cache ~FETCH_LEAD before the scheduled tick so network
@@ -131,7 +248,28 @@ fn background(config: config::Config) -> AnyhowResult<()> {
         // next successful fetch) plus a log entry — not a notification
         let fetch_error = feed.fetch(Utc::now());
         tray::set_warning(fetch_error);
-        sleep_until(scheduled);
+        // once per launch, a quiet confirmation that the feed works
+        if !startup_confirmed && feed.fetched_ok() {
+            startup_confirmed = true;
+            let now = Utc::now();
+            let message = logic::startup_message(&feed.cal(now), now);
+            info!("calendar loaded: {message}");
+            notifications::send(
+                "Nextcall",
+                Some("Watching your calendar"),
+                &message,
+                None,
+                None,
+                None,
+                Interruption::Passive,
+            );
+        }
+        if let Some(wake) = sleep_until(scheduled, wakes) {
+            if on_wake(wake, fetch_error, &mut config, &mut feed) {
+                scheduled = Utc::now();
+            }
+            continue;
+        }
 
         let now = Utc::now();
         // cache hit: re-selects the calendar window at the tick itself, so
@@ -148,15 +286,92 @@ fn background(config: config::Config) -> AnyhowResult<()> {
             info!("dismissed call: {dismissed:?}");
             prev_dismissed = dismissed;
         }
-        let camera_active = camera::camera_active();
-        let step = logic::step(&cal, now, prev_tick, camera_active, dismissed);
+        // "Silence This Call" keeps the call's alerts but drops their sound
+        // and speech; matched like a dismissal
+        let silenced = tray::silenced_ts()
+            .is_some_and(|ts| cal.next_call.as_ref().is_some_and(|e| e.start_time.timestamp() == ts));
+        if silenced != prev_silenced {
+            info!("call silenced: {silenced}");
+            prev_silenced = silenced;
+        }
+        // the notifications' Snooze button mutes the call for a while
+        let snooze_minutes = config.snooze_minutes.unwrap_or(notifications::DEFAULT_SNOOZE_MINUTES);
+        let snoozed = logic::snoozed(&cal, now, notifications::snoozed(), i64::from(snooze_minutes));
+        if snoozed != prev_snoozed {
+            info!("snoozed call: {snoozed:?}");
+            prev_snoozed = snoozed;
+        }
+        let in_call = camera::in_call(&config);
+        // a call in progress at launch gets one alert with its real age (none
+        // with quiet_launch, or if the user is already on it) instead of
+        // starting its reminder sequence part-way through; then it is muted.
+        // Settled only once the calendar is known, so a call that only shows
+        // up when an offline start gets the network counts as well
+        let launch_call = if feed.loaded() {
+            *launch_call.get_or_insert_with(|| {
+                let call = logic::in_progress(&cal, now);
+                if let (Some(start), Some(event)) = (call, &cal.next_call) {
+                    if config.quiet_launch.unwrap_or(false) || in_call {
+                        info!("muting call in progress at launch, started {start}");
+                    } else {
+                        logic::fire_alert(event, (now - start).num_minutes(), in_call, false, &config);
+                    }
+                }
+                call
+            })
+        } else {
+            None
+        };
+        let paused = tray::paused();
+        if paused != prev_paused {
+            info!("notifications paused: {paused}");
+            prev_paused = paused;
+        }
+        // the launch's call only matches while it is still next_call;
+        // pausing mutes whichever call is next
+        let muted = dismissed.or(snoozed).or_else(|| {
+            let event = cal.next_call.as_ref()?;
+            launch_call
+                .filter(|start| *start == event.start_time)
+                .or(paused.then_some(event.start_time))
+        });
+        if let Some(start) = logic::joined(&cal, now, in_call, prev_in_call)
+            && joined != Some(start)
+        {
+            info!("joined call started {start}, stopping reminders");
+            joined = Some(start);
+            // the call's reminders are stale now
+            notifications::clear_delivered();
+        }
+        prev_in_call = in_call;
+        // only consult the power source when it can make a difference
+        let on_battery = config.battery_check_interval_secs.is_some() && power::on_battery();
+        let settings = logic::Settings::new(&config, on_battery);
+        let step = logic::step(&cal, now, prev_tick, in_call, muted, joined, &settings);
         tray::set_status(&step.status);
+        tray::set_tooltip(&step.tooltip);
         tray::set_title(&step.title);
-        // arm the menu's Dismiss item with the call it would act on
-        // (0 disables it: no upcoming call) and expire a stale dismissal
+        tray::set_all_clear(cal.next_call.is_none());
+        if !config.monochrome_icon.unwrap_or(false) {
+            tray::set_urgency(step.urgency);
+        }
+        tray::set_progress(step.progress.filter(|_| config.progress_ring.unwrap_or(false)));
+        // arm the menu's Dismiss and Silence items with the call they would
+        // act on (0 disables them: no upcoming call) and expire stale ones
         tray::set_dismiss_target(cal.next_call.as_ref().map_or(0, |e| e.start_time.timestamp()));
+        tray::set_join(cal.next_call.as_ref());
+        let upcoming = feed.upcoming(now, UPCOMING_MENU_LEN);
+        if upcoming != prev_upcoming {
+            tray::set_upcoming(&upcoming);
+            prev_upcoming = upcoming;
+        }
+        let attendance = history::attendance(&feed.calls_today(now));
+        if attendance != prev_attendance {
+            tray::set_attendance(&attendance);
+            prev_attendance = attendance;
+        }
         if let Some((event, minutes)) = step.alert {
-            logic::fire_alert(&event, minutes, camera_active, config.eleven_labs_key.as_deref());
+            logic::fire_alert(&event, minutes, in_call, silenced, &config);
         }
 
         prev_tick = now;
@@ -167,15 +382,88 @@ fn background(config: config::Config) -> AnyhowResult<()> {
             scheduled.format("%H:%M:%S")
         );
         // long leg of the sleep; zero when the next tick is <= FETCH_LEAD away
-        sleep_until(scheduled - FETCH_LEAD);
+        if let Some(wake) = sleep_until(scheduled - FETCH_LEAD, wakes)
+            && on_wake(wake, fetch_error, &mut config, &mut feed)
+        {
+            scheduled = Utc::now();
+        }
     }
 }
 
-/// Sleeps until the wall-clock instant `t` (no-op if already past). Wall time
-/// rather than `Instant`: `Instant` doesn't advance during system sleep, and
-/// alert firing can block for seconds; recomputing keeps ticks on schedule.
-fn sleep_until(t: DateTime<Utc>) {
-    if let Ok(duration) = t.signed_duration_since(Utc::now()).to_std() {
-        sleep(duration.min(logic::DEFAULT_CHECK_INTERVAL));
+/// Handles an early wake-up, returning whether the loop should tick right
+/// away rather than carry on towards the scheduled tick.
+fn on_wake(wake: Wake, fetch_error: bool, config: &mut config::Config, feed: &mut ical::CalendarFeed) -> bool {
+    match wake {
+        // connectivity is back and the last fetch failed: refetch now
+        // rather than waiting out the cache TTL
+        Wake::NetworkUp if fetch_error => {
+            info!("network is back, refetching calendar");
+            feed.expire();
+            true
+        }
+        Wake::NetworkUp => false,
+        Wake::Refresh => {
+            info!("refresh requested, refetching calendar");
+            feed.expire();
+            true
+        }
+        // a bad edit keeps the running config rather than stopping the app
+        Wake::Reload => {
+            let failed = |message: &str| {
+                error!("failed to reload configuration: {message}");
+                notifications::send(
+                    "Nextcall Configuration",
+                    Some("Configuration not reloaded"),
+                    message,
+                    None,
+                    None,
+                    None,
+                    // informational: a live call's Join must stay
+                    Interruption::Passive,
+                );
+            };
+            match config::get_config() {
+                Ok(Some(new_config)) => match ical::CalendarFeed::new(&new_config) {
+                    Ok(new_feed) => {
+                        info!("configuration reloaded: {new_config}");
+                        MAX_LOG_BYTES.store(
+                            new_config.max_log_bytes.unwrap_or(DEFAULT_MAX_LOG_BYTES),
+                            Ordering::Relaxed,
+                        );
+                        tray::set_preferences(&config::Preferences::new(&new_config));
+                        tray::set_about(config::get_config_path().ok().flatten().as_deref());
+                        notifications::set_snooze_minutes(
+                            new_config
+                                .snooze_minutes
+                                .unwrap_or(notifications::DEFAULT_SNOOZE_MINUTES),
+                        );
+                        browser::set_command(new_config.browser_command.clone());
+                        *config = new_config;
+                        *feed = new_feed;
+                    }
+                    Err(err) => failed(&err.to_string()),
+                },
+                Ok(None) => failed("nextcall.toml not found"),
+                Err(err) => failed(&format!("{err:#}")),
+            }
+            true
+        }
+    }
+}
+
+/// Sleeps until the wall-clock instant `t` (no-op if already past), returning
+/// early with any [`Wake`] received meanwhile. Wall time rather than
+/// `Instant`: `Instant` doesn't advance during system sleep, and a tick can
+/// run late (e.g. a slow fetch); recomputing keeps ticks on schedule. The
+/// wait is re-checked at least every [`logic::DEFAULT_CHECK_INTERVAL`], so a
+/// long sleep (`check_interval_secs`) still notices a system sleep passing.
+fn sleep_until(t: DateTime<Utc>, wakes: &Receiver<Wake>) -> Option<Wake> {
+    loop {
+        let duration = t.signed_duration_since(Utc::now()).to_std().ok()?;
+        match wakes.recv_timeout(duration.min(logic::DEFAULT_CHECK_INTERVAL)) {
+            Ok(wake) => return Some(wake),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
     }
 }
@@ -0,0 +1,14 @@
+//! Microphone-activity detection, backed by the implementation in
+//! `src/native/mic.m` (CoreAudio hardware API).
+//!
+//! Complements [`crate::camera`] so audio-only calls count as "in a call".
+
+unsafe extern "C" {
+    fn mic_is_active() -> bool;
+}
+
+/// Returns true if any audio input device is in use by some process. Like
+/// [`crate::camera::camera_active`], involves a device enumeration.
+pub fn mic_active() -> bool {
+    unsafe { mic_is_active() }
+}
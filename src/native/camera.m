@@ -1,11 +1,15 @@
-// Camera-activity detection via the CoreMediaIO hardware C API.
+// Camera-activity detection via the CoreMediaIO hardware C API, plus a
+// screen-sharing heuristic via the CoreGraphics window list.
 //
-// Exposed to Rust as camera_is_active() (declared in src/camera.rs). Used to
+// Exposed to Rust as camera_is_active() and screen_is_shared() (declared in
+// src/camera.rs). Used to
 // avoid interrupting an ongoing call with notifications/speech. Enumerates
 // CMIO devices directly (they are all video/camera devices) rather than going
 // through AVFoundation, whose device->CMIO bridge (connectionID) was removed
 // from recent SDKs.
+#import <CoreGraphics/CoreGraphics.h>
 #import <CoreMediaIO/CMIOHardware.h>
+#import <Foundation/Foundation.h>
 #include <stdbool.h>
 #include <stdlib.h>
 
@@ -58,3 +62,38 @@ bool camera_is_active(void) {
     free(devices);
     return active;
 }
+
+// Titles (or parts) of the sharing controls meeting apps put on screen while
+// presenting: Zoom's share toolbar, Teams' sharing bar, and Chrome's "... is
+// sharing your screen" bar (Meet and other web calls).
+static NSArray<NSString *> *sharingMarkers(void) {
+    return @[ @"zoom share toolbar", @"zoom share statusbar", @"sharing control bar", @"is sharing your screen",
+              @"is sharing a window" ];
+}
+
+// Returns true if an on-screen window looks like a meeting app's "you are
+// sharing" control. A heuristic: macOS has no public "screen is being
+// captured" query, and other apps' window titles are only visible with the
+// Screen Recording permission (without it this always returns false).
+bool screen_is_shared(void) {
+    @autoreleasepool {
+        CFArrayRef windows = CGWindowListCopyWindowInfo(
+            kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements, kCGNullWindowID);
+        if (windows == NULL) {
+            return false;
+        }
+        NSArray<NSDictionary *> *infos = CFBridgingRelease(windows);
+        for (NSDictionary *info in infos) {
+            NSString *title = [info[(__bridge NSString *)kCGWindowName] lowercaseString];
+            if (title.length == 0) {
+                continue;
+            }
+            for (NSString *marker in sharingMarkers()) {
+                if ([title containsString:marker]) {
+                    return true;
+                }
+            }
+        }
+        return false;
+    }
+}
@@ -0,0 +1,132 @@
+// Stand-ins for the macOS helpers in this directory, compiled instead of them
+// on other platforms (see build.rs) so the core countdown builds and runs
+// there: the calendar is polled and alerts fire as desktop notifications via
+// notify-send, but there is no menu bar item and nothing is detected (no
+// camera, mic, Focus or battery), so the loop never thinks a call is joined.
+//
+// Signatures match the .m files exactly; the Rust declarations are shared.
+#include <spawn.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+extern char **environ;
+
+bool camera_is_active(void) { return false; }
+
+bool screen_is_shared(void) { return false; }
+
+bool mic_is_active(void) { return false; }
+
+void focus_startup(void) {}
+
+bool focus_is_active(void) { return false; }
+
+bool power_on_battery(void) { return false; }
+
+// Reports the network as up once; there is no path monitor to say otherwise.
+void network_watch(void (*on_change)(bool connected)) { on_change(true); }
+
+void notifications_startup(void (*on_snooze)(int64_t start_ts), bool (*on_open_link)(const char *url),
+                           void (*on_join)(int64_t start_ts, const char *url),
+                           void (*on_opened)(const char *url, const char *error)) {
+    (void)on_snooze;
+    (void)on_open_link;
+    (void)on_join;
+    (void)on_opened;
+}
+
+void notifications_set_snooze_minutes(uint32_t snooze_minutes) { (void)snooze_minutes; }
+
+void notifications_clear_delivered(void) {}
+
+// Shows the notification with notify-send (libnotify), best effort: without
+// it nothing is shown. There are no buttons, so url and start_ts go unused;
+// sound is up to the notification daemon. A passive one is sent at low
+// urgency.
+void notifications_send(const char *title, const char *subtitle, const char *body, const char *url,
+                        const char *fallback_url, int64_t start_ts, bool sound, bool passive) {
+    (void)url;
+    (void)fallback_url;
+    (void)start_ts;
+    (void)sound;
+    const char *summary = subtitle != NULL ? subtitle : title;
+    char *argv[] = {"notify-send", "--app-name", (char *)title, "--urgency", passive ? "low" : "normal",
+                    (char *)summary, (char *)body, NULL};
+    pid_t pid;
+    if (posix_spawnp(&pid, "notify-send", NULL, NULL, argv, environ) == 0) {
+        waitpid(pid, NULL, 0);
+    }
+}
+
+// No menu bar: blocks the main thread for good while the Rust loop runs on
+// its own thread, as the AppKit event loop would.
+void tray_run(void (*on_refresh)(void),
+              bool (*on_save_preferences)(const char *ical_url, const char *eleven_labs_key,
+                                          const char *builtin_voice, const char *notify_before_minutes, bool force,
+                                          char *error, size_t error_len),
+              bool (*on_open_link)(const char *url), void (*on_join)(int64_t start_ts, const char *url)) {
+    (void)on_refresh;
+    (void)on_save_preferences;
+    (void)on_open_link;
+    (void)on_join;
+    for (;;) {
+        pause();
+    }
+}
+
+void tray_set_title(const char *title) { (void)title; }
+
+void tray_set_status(const char *status) { (void)status; }
+
+void tray_set_tooltip(const char *tooltip) { (void)tooltip; }
+
+void tray_set_dismiss_target(int64_t start_ts) { (void)start_ts; }
+
+void tray_set_preferences(const char *ical_url, const char *eleven_labs_key, const char *builtin_voice,
+                          const char *notify_before_minutes) {
+    (void)ical_url;
+    (void)eleven_labs_key;
+    (void)builtin_voice;
+    (void)notify_before_minutes;
+}
+
+void tray_set_about(const char *version, const char *config_path, const char *config_search) {
+    (void)version;
+    (void)config_path;
+    (void)config_search;
+}
+
+void tray_set_attendance(const char *attendance) { (void)attendance; }
+
+void tray_set_join(const char *summary, const char *url, const char *fallback_url) {
+    (void)summary;
+    (void)url;
+    (void)fallback_url;
+}
+
+void tray_clear_upcoming(void) {}
+
+void tray_add_upcoming(const char *label, const char *url, const char *fallback_url) {
+    (void)label;
+    (void)url;
+    (void)fallback_url;
+}
+
+void tray_set_warning(bool warning) { (void)warning; }
+
+void tray_set_all_clear(bool all_clear) { (void)all_clear; }
+
+void tray_set_progress(double progress) { (void)progress; }
+
+void tray_set_urgency(int urgency) { (void)urgency; }
+
+int64_t tray_dismissed_ts(void) { return 0; }
+
+int64_t tray_silenced_ts(void) { return 0; }
+
+bool tray_paused(void) { return false; }
+
+void tray_set_log_path(const char *path) { (void)path; }
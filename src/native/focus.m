@@ -0,0 +1,38 @@
+// Focus (Do Not Disturb) state via the Intents framework's INFocusStatusCenter.
+//
+// Exposed to Rust as focus_startup() and focus_is_active() (declared in
+// src/focus.rs). Used to keep announcements silent while a Focus is on. The
+// app only learns the state once the user grants "Focus status" access, which
+// focus_startup requests (NSFocusStatusUsageDescription in Info.plist).
+#import <Intents/Intents.h>
+#include <stdbool.h>
+#include <stdio.h>
+
+// Requests access to the Focus status, prompting on first launch only. Call
+// once at startup.
+void focus_startup(void) {
+    @autoreleasepool {
+        INFocusStatusCenter *center = [INFocusStatusCenter defaultCenter];
+        if (center.authorizationStatus != INFocusStatusAuthorizationStatusNotDetermined) {
+            return;
+        }
+        [center requestAuthorizationWithCompletionHandler:^(INFocusStatusAuthorizationStatus status) {
+          if (status != INFocusStatusAuthorizationStatusAuthorized) {
+              fprintf(stderr, "✗ Focus status access denied - announcements will ignore Focus\n");
+          }
+        }];
+    }
+}
+
+// Returns true if a Focus is on. False when the state is unknown (access not
+// granted), so announcements err on the side of speaking.
+bool focus_is_active(void) {
+    @autoreleasepool {
+        INFocusStatusCenter *center = [INFocusStatusCenter defaultCenter];
+        if (center.authorizationStatus != INFocusStatusAuthorizationStatusAuthorized) {
+            return false;
+        }
+        NSNumber *focused = center.focusStatus.isFocused;
+        return focused != nil && focused.boolValue;
+    }
+}
@@ -0,0 +1,69 @@
+// Microphone-activity detection via the CoreAudio hardware C API.
+//
+// Exposed to Rust as mic_is_active() (declared in src/mic.rs). The audio
+// counterpart of camera.m: CoreMediaIO only covers video devices, so
+// audio-only calls need CoreAudio's equivalent "running somewhere" property.
+#import <CoreAudio/CoreAudio.h>
+#include <stdbool.h>
+#include <stdlib.h>
+
+// Returns true if the device has at least one input stream, i.e. is a
+// microphone (or an aggregate including one) rather than output only.
+static bool has_input(AudioObjectID device) {
+    AudioObjectPropertyAddress streamsAddress = {
+        .mSelector = kAudioDevicePropertyStreams,
+        .mScope = kAudioObjectPropertyScopeInput,
+        .mElement = kAudioObjectPropertyElementMain,
+    };
+    UInt32 dataSize = 0;
+    return AudioObjectGetPropertyDataSize(device, &streamsAddress, 0, NULL, &dataSize) == kAudioHardwareNoError &&
+           dataSize > 0;
+}
+
+// Returns true if any input device reports it is in use by some process
+// (kAudioDevicePropertyDeviceIsRunningSomewhere), i.e. a call is likely live.
+bool mic_is_active(void) {
+    // Ask the system object for the list of all audio device IDs.
+    AudioObjectPropertyAddress devicesAddress = {
+        .mSelector = kAudioHardwarePropertyDevices,
+        .mScope = kAudioObjectPropertyScopeGlobal,
+        .mElement = kAudioObjectPropertyElementMain,
+    };
+    UInt32 dataSize = 0;
+    if (AudioObjectGetPropertyDataSize(kAudioObjectSystemObject, &devicesAddress, 0, NULL, &dataSize) !=
+            kAudioHardwareNoError ||
+        dataSize == 0) {
+        return false;
+    }
+
+    UInt32 deviceCount = dataSize / sizeof(AudioObjectID);
+    AudioObjectID *devices = calloc(deviceCount, sizeof(AudioObjectID));
+    if (devices == NULL) {
+        return false;
+    }
+    if (AudioObjectGetPropertyData(kAudioObjectSystemObject, &devicesAddress, 0, NULL, &dataSize, devices) !=
+        kAudioHardwareNoError) {
+        free(devices);
+        return false;
+    }
+
+    AudioObjectPropertyAddress runningAddress = {
+        .mSelector = kAudioDevicePropertyDeviceIsRunningSomewhere,
+        .mScope = kAudioObjectPropertyScopeGlobal,
+        .mElement = kAudioObjectPropertyElementMain,
+    };
+
+    bool active = false;
+    for (UInt32 i = 0; i < deviceCount && !active; i++) {
+        UInt32 isRunning = 0;
+        UInt32 size = sizeof(isRunning);
+        if (has_input(devices[i]) &&
+            AudioObjectGetPropertyData(devices[i], &runningAddress, 0, NULL, &size, &isRunning) ==
+                kAudioHardwareNoError &&
+            isRunning != 0) {
+            active = true;
+        }
+    }
+    free(devices);
+    return active;
+}
@@ -0,0 +1,24 @@
+// Network reachability via the Network framework's path monitor.
+//
+// Exposed to Rust as network_watch() (declared in src/network.rs). Used to
+// refetch the calendar the moment connectivity returns after a failure,
+// rather than waiting out the cache TTL.
+#import <Network/Network.h>
+#include <stdbool.h>
+
+// Created once in network_watch; lives for the process lifetime. Network
+// objects are ObjC objects under ARC, so this strong reference keeps the
+// monitor (and its update handler) alive.
+static nw_path_monitor_t gMonitor = nil;
+
+// Starts watching the default network path, calling on_change(connected) on
+// a background queue with the initial state and then on every path update
+// (these can repeat the same state). Call once.
+void network_watch(void (*on_change)(bool connected)) {
+    gMonitor = nw_path_monitor_create();
+    nw_path_monitor_set_queue(gMonitor, dispatch_get_global_queue(QOS_CLASS_UTILITY, 0));
+    nw_path_monitor_set_update_handler(gMonitor, ^(nw_path_t path) {
+      on_change(nw_path_get_status(path) == nw_path_status_satisfied);
+    });
+    nw_path_monitor_start(gMonitor);
+}
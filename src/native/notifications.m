@@ -7,13 +7,40 @@
 #import <AppKit/AppKit.h>
 #import <Foundation/Foundation.h>
 #import <UserNotifications/UserNotifications.h>
+#import <stdbool.h>
 
 // Identifiers shared between category registration (startup) and send.
 static NSString *const kMeetingCategory = @"MEETING_CATEGORY";
 static NSString *const kJoinAction = @"JOIN_ACTION";
+static NSString *const kCopyLinkAction = @"COPY_LINK_ACTION";
+static NSString *const kSnoozeAction = @"SNOOZE_ACTION";
+
+// Rust callback for the Snooze button, passed to notifications_startup: gets
+// the snoozed call's start (unix seconds).
+static void (*gOnSnooze)(int64_t start_ts) = NULL;
+
+// Rust's browser_command hook, passed to notifications_startup: opens an
+// http(s) link itself and returns true, or returns false to leave it to us.
+static bool (*gOnOpenLink)(const char *url) = NULL;
+
+// Rust's join history hook, passed to notifications_startup: records that the
+// call starting at start_ts (unix seconds, 0 if unknown) was joined via url.
+static void (*gOnJoin)(int64_t start_ts, const char *url) = NULL;
+
+// Rust's logging hook, passed to notifications_startup: told whether a link
+// from a notification opened (error NULL) or why not.
+static void (*gOnOpened)(const char *url, const char *error) = NULL;
+
+// Reports an open's outcome to gOnOpened, if set.
+static void report_opened(NSString *url, NSString *error) {
+    if (gOnOpened != NULL) {
+        gOnOpened(url.UTF8String, error.UTF8String);
+    }
+}
 
 // Delegate that keeps banners visible while the app is frontmost and opens
-// the meeting URL when the notification (or its Join button) is clicked.
+// the meeting URL when the notification (or its Join button) is clicked,
+// copies it with the Copy Link button, or reports the Snooze button to Rust.
 @interface NCNotificationDelegate : NSObject <UNUserNotificationCenterDelegate>
 @end
 
@@ -32,8 +59,22 @@ static NSString *const kJoinAction = @"JOIN_ACTION";
 // app — which can win the activation race against the browser and leave its
 // window frontmost but *inactive* (mouse clicks swallowed, hover broken).
 // activates=YES plus cooperative yielding makes the handoff deterministic.
-static void open_meeting_url(NSURL *nsurl) {
+// A native-app link (zoommtg://, msteams:) with no app to handle it falls back
+// to the https fallback link, if any. An http(s) link goes to gOnOpenLink
+// first, for a configured browser_command (which logs for itself); otherwise
+// the outcome is reported to gOnOpened. on_opened runs once the link (or the
+// fallback) has actually opened.
+static void open_meeting_url(NSURL *nsurl, NSURL *fallback, void (^on_opened)(void)) {
     NSWorkspace *ws = [NSWorkspace sharedWorkspace];
+    if (fallback != nil && [ws URLForApplicationToOpenURL:nsurl] == nil) {
+        nsurl = fallback;
+    }
+    NSString *scheme = nsurl.scheme.lowercaseString;
+    if (([scheme isEqualToString:@"http"] || [scheme isEqualToString:@"https"]) && gOnOpenLink != NULL &&
+        gOnOpenLink(nsurl.absoluteString.UTF8String)) {
+        on_opened();
+        return;
+    }
     // macOS 14+ cooperative activation: declare that the URL's handler may take
     // focus from us, otherwise its activation request can be silently refused.
     if (@available(macOS 14.0, *)) {
@@ -45,21 +86,58 @@ static void open_meeting_url(NSURL *nsurl) {
     }
     NSWorkspaceOpenConfiguration *config = [NSWorkspaceOpenConfiguration configuration];
     config.activates = YES;
-    [ws openURL:nsurl configuration:config completionHandler:nil];
+    NSString *link = nsurl.absoluteString;
+    [ws openURL:nsurl
+        configuration:config
+    completionHandler:^(NSRunningApplication *_Nullable app, NSError *_Nullable error) {
+      report_opened(link, error != nil ? error.localizedDescription : nil);
+      if (error == nil) {
+          on_opened();
+      }
+    }];
 }
 
-// Any interaction (banner click or Join button) opens the stored video link.
+// Copy Link puts the stored link on the pasteboard - the https one when the
+// notification opens a native-app link, as that is what pastes usefully into
+// chat. Any other interaction (banner click or Join button) opens it.
 - (void)userNotificationCenter:(UNUserNotificationCenter *)center
     didReceiveNotificationResponse:(UNNotificationResponse *)response
              withCompletionHandler:(void (^)(void))completionHandler {
-    NSString *url = response.notification.request.content.userInfo[@"url"];
-    if ([url isKindOfClass:[NSString class]]) {
+    NSDictionary *userInfo = response.notification.request.content.userInfo;
+    NSString *url = userInfo[@"url"];
+    NSString *fallbackURL = userInfo[@"fallback_url"];
+    if ([response.actionIdentifier isEqualToString:kSnoozeAction]) {
+        NSNumber *start = userInfo[@"start"];
+        if ([start isKindOfClass:[NSNumber class]] && gOnSnooze != NULL) {
+            gOnSnooze(start.longLongValue);
+        }
+    } else if ([response.actionIdentifier isEqualToString:kCopyLinkAction]) {
+        NSString *link = [fallbackURL isKindOfClass:[NSString class]] ? fallbackURL : url;
+        if ([link isKindOfClass:[NSString class]]) {
+            dispatch_async(dispatch_get_main_queue(), ^{
+              NSPasteboard *pasteboard = [NSPasteboard generalPasteboard];
+              [pasteboard clearContents];
+              [pasteboard setString:link forType:NSPasteboardTypeString];
+            });
+        }
+    } else if ([url isKindOfClass:[NSString class]]) {
         NSURL *nsurl = [NSURL URLWithString:url];
-        if (nsurl != nil) {
+        NSURL *fallback = [fallbackURL isKindOfClass:[NSString class]] ? [NSURL URLWithString:fallbackURL] : nil;
+        // a link without a scheme has nothing to open it; don't try
+        if (nsurl == nil || nsurl.scheme.length == 0) {
+            report_opened(url, @"not a valid URL");
+        } else {
+            NSNumber *start = userInfo[@"start"];
+            int64_t start_ts = [start isKindOfClass:[NSNumber class]] ? start.longLongValue : 0;
             // Delegate callbacks arrive on a background queue; NSApp
             // (activation yielding) must be touched on the main thread.
             dispatch_async(dispatch_get_main_queue(), ^{
-              open_meeting_url(nsurl);
+              // only a link that opened counts as a join
+              open_meeting_url(nsurl, fallback, ^{
+                if (gOnJoin != NULL) {
+                    gOnJoin(start_ts, url.UTF8String);
+                }
+              });
             });
         }
     }
@@ -72,10 +150,47 @@ static void open_meeting_url(NSURL *nsurl) {
 // what keeps the delegate alive for the process lifetime.
 static NCNotificationDelegate *gDelegate = nil;
 
-// Installs the delegate, requests notification permission and registers the
-// MEETING_CATEGORY with a "Join" action. Call once, before notifications_send.
-void notifications_startup(void) {
+// Registers the MEETING_CATEGORY with "Join", "Copy Link" and "Snooze <N> min"
+// actions. Re-registering replaces the category, so the Snooze title follows
+// the configured snooze length.
+void notifications_set_snooze_minutes(uint32_t snooze_minutes) {
+    @autoreleasepool {
+        // Background actions (options:0): Foreground would activate *nextcall*,
+        // racing the browser's activation from open_meeting_url and leaving the
+        // browser's window frontmost but not accepting mouse input properly.
+        UNNotificationAction *join = [UNNotificationAction actionWithIdentifier:kJoinAction
+                                                                          title:@"Join"
+                                                                        options:0];
+        UNNotificationAction *copyLink = [UNNotificationAction actionWithIdentifier:kCopyLinkAction
+                                                                              title:@"Copy Link"
+                                                                            options:0];
+        NSString *snoozeTitle = [NSString stringWithFormat:@"Snooze %u min", snooze_minutes];
+        UNNotificationAction *snooze = [UNNotificationAction actionWithIdentifier:kSnoozeAction
+                                                                            title:snoozeTitle
+                                                                          options:0];
+        UNNotificationCategory *category = [UNNotificationCategory categoryWithIdentifier:kMeetingCategory
+                                                                                   actions:@[ join, copyLink, snooze ]
+                                                                         intentIdentifiers:@[]
+                                                                                   options:0];
+        [[UNUserNotificationCenter currentNotificationCenter]
+            setNotificationCategories:[NSSet setWithObject:category]];
+    }
+}
+
+// Installs the delegate and requests notification permission; on_snooze is
+// called, on a background queue, when a Snooze button is pressed, and
+// on_open_link (main thread) is offered each http(s) link before it is opened,
+// on_join (any thread) is told of each call whose link opened from a
+// notification, and on_opened (any thread) of whether its link opened. Call
+// once, then notifications_set_snooze_minutes, before notifications_send.
+void notifications_startup(void (*on_snooze)(int64_t start_ts), bool (*on_open_link)(const char *url),
+                           void (*on_join)(int64_t start_ts, const char *url),
+                           void (*on_opened)(const char *url, const char *error)) {
     @autoreleasepool {
+        gOnSnooze = on_snooze;
+        gOnOpenLink = on_open_link;
+        gOnJoin = on_join;
+        gOnOpened = on_opened;
         UNUserNotificationCenter *center = [UNUserNotificationCenter currentNotificationCenter];
 
         gDelegate = [[NCNotificationDelegate alloc] init];
@@ -95,25 +210,27 @@ void notifications_startup(void) {
                                     }
                                 }
                               }];
+    }
+}
 
-        // Background action (options:0): Foreground would activate *nextcall*,
-        // racing the browser's activation from open_meeting_url and leaving the
-        // browser's window frontmost but not accepting mouse input properly.
-        UNNotificationAction *join = [UNNotificationAction actionWithIdentifier:kJoinAction
-                                                                          title:@"Join"
-                                                                        options:0];
-        UNNotificationCategory *category = [UNNotificationCategory categoryWithIdentifier:kMeetingCategory
-                                                                                   actions:@[ join ]
-                                                                         intentIdentifiers:@[]
-                                                                                   options:0];
-        [center setNotificationCategories:[NSSet setWithObject:category]];
+// Removes every delivered notification from Notification Center (they are all
+// ours). Thread-safe like notifications_send.
+void notifications_clear_delivered(void) {
+    @autoreleasepool {
+        [[UNUserNotificationCenter currentNotificationCenter] removeAllDeliveredNotifications];
     }
 }
 
-// Posts a notification immediately. subtitle and url may be NULL; a non-NULL
-// url adds the Join button and makes any click open that link.
+// Posts a notification immediately. subtitle, url and fallback_url may be
+// NULL; a non-NULL url adds the Join, Copy Link and Snooze buttons and makes
+// any other click open that link (or fallback_url when no app handles url's
+// scheme). start_ts is the call's start (unix seconds) reported by Snooze;
+// sound false posts a silent banner. passive posts an informational one at
+// the passive interruption level, without sound, leaving earlier
+// notifications (e.g. a live call's Join) in place.
 // Thread-safe: UNUserNotificationCenter may be called from any thread.
-void notifications_send(const char *title, const char *subtitle, const char *body, const char *url) {
+void notifications_send(const char *title, const char *subtitle, const char *body, const char *url,
+                        const char *fallback_url, int64_t start_ts, bool sound, bool passive) {
     @autoreleasepool {
         UNMutableNotificationContent *content = [[UNMutableNotificationContent alloc] init];
         content.title = @(title);
@@ -122,14 +239,23 @@ void notifications_send(const char *title, const char *subtitle, const char *bod
             content.subtitle = @(subtitle);
         }
 
-        // "Blow" with active interruption level so the alert reliably makes sound.
-        content.sound = [UNNotificationSound soundNamed:@"Blow.aiff"];
-        content.interruptionLevel = UNNotificationInterruptionLevelActive;
+        // "Blow" with active interruption level so the alert reliably makes
+        // sound; passive ones only go to Notification Center, quietly.
+        if (sound && !passive) {
+            content.sound = [UNNotificationSound soundNamed:@"Blow.aiff"];
+        }
+        content.interruptionLevel =
+            passive ? UNNotificationInterruptionLevelPassive : UNNotificationInterruptionLevelActive;
 
         if (url != NULL) {
             content.categoryIdentifier = kMeetingCategory;
-            // The delegate reads this back out when the notification is clicked.
-            content.userInfo = @{@"url" : @(url)};
+            // The delegate reads these back out when the notification is clicked.
+            NSMutableDictionary *userInfo = [NSMutableDictionary dictionaryWithObject:@(url) forKey:@"url"];
+            if (fallback_url != NULL) {
+                userInfo[@"fallback_url"] = @(fallback_url);
+            }
+            userInfo[@"start"] = @(start_ts);
+            content.userInfo = userInfo;
         }
 
         NSString *identifier = [NSString stringWithFormat:@"nextcall-%@", [[NSUUID UUID] UUIDString]];
@@ -141,8 +267,11 @@ void notifications_send(const char *title, const char *subtitle, const char *bod
         // Only the newest alert should be visible: clear any still-unanswered
         // banners (every delivered notification is ours) before posting. A fresh
         // identifier is used per alert because replacing a delivered request
-        // in-place does not reliably re-play the sound.
-        [center removeAllDeliveredNotifications];
+        // in-place does not reliably re-play the sound. A passive one leaves
+        // them be: it must not take away a call's Join.
+        if (!passive) {
+            [center removeAllDeliveredNotifications];
+        }
         [center addNotificationRequest:request
                  withCompletionHandler:^(NSError *_Nullable error) {
                    if (error != nil) {
@@ -0,0 +1,19 @@
+// Power-source detection via IOKit's power sources API.
+//
+// Exposed to Rust as power_on_battery() (declared in src/power.rs).
+#import <IOKit/ps/IOPSKeys.h>
+#import <IOKit/ps/IOPowerSources.h>
+#include <stdbool.h>
+
+// Returns true if the Mac is running on battery rather than AC power (false
+// on a desktop, or if the power sources can't be read).
+bool power_on_battery(void) {
+    CFTypeRef info = IOPSCopyPowerSourcesInfo();
+    if (info == NULL) {
+        return false;
+    }
+    CFStringRef type = IOPSGetProvidingPowerSourceType(info);
+    bool battery = type != NULL && CFEqual(type, CFSTR(kIOPSBatteryPowerValue));
+    CFRelease(info);
+    return battery;
+}
@@ -2,9 +2,10 @@
 //
 // Replaces the old winit + tray-icon + rendered-PNG approach: NSStatusItem
 // displays plain text natively, so the countdown is just a title string. The
-// only images are template glyphs: the logo while idle, bell.slash while
-// dismissed, exclamationmark.triangle while calendar fetches fail. Exposed to
-// Rust as tray_run / tray_set_title (src/tray.rs).
+// only images are template glyphs: the logo while idle (a checkmark with no
+// call at all), bell.slash while dismissed, exclamationmark.triangle while
+// calendar fetches fail. Exposed to Rust as tray_run / tray_set_title
+// (src/tray.rs).
 #import <AppKit/AppKit.h>
 #import <stdatomic.h>
 #import <stdbool.h>
@@ -13,28 +14,148 @@
 // before NCMenuActions because its openLog: action reads it.
 static NSString *gLogPath = nil;
 
+// The app version, the config file in use (nil = none found) and the
+// locations searched for it, in order, shown by "About Nextcall"; set via
+// tray_set_about.
+static NSString *gVersion = nil;
+static NSString *gConfigPath = nil;
+static NSString *gConfigSearch = nil;
+// "Joined N of M calls today", also shown by "About Nextcall"; set via
+// tray_set_attendance.
+static NSString *gAttendance = nil;
+
+// Rust callbacks for the "Refresh Now" and "Preferences…" items, passed to
+// tray_run. Declared before NCMenuActions because its actions call them.
+static void (*gOnRefresh)(void) = NULL;
+// gOnSavePreferences validates (unless force) and saves the fields (empty =
+// unset), returning false with a message in error on failure.
+static bool (*gOnSavePreferences)(const char *ical_url, const char *eleven_labs_key, const char *builtin_voice,
+                                  const char *notify_before_minutes, bool force, char *error,
+                                  size_t error_len) = NULL;
+// The open Preferences alert, its fields, spinner and progress label, and
+// the outcome of its last save (nil = saved); main thread only, set while the
+// alert is up.
+static NSAlert *gPreferencesAlert = nil;
+static NSArray<NSTextField *> *gPreferenceFields = nil;
+static NSProgressIndicator *gPreferencesSpinner = nil;
+static NSTextField *gPreferencesProgress = nil;
+static NSString *gPreferencesFailure = nil;
+// Rust's browser_command hook: opens an http(s) link itself and returns true,
+// or returns false to leave it to NSWorkspace.
+static bool (*gOnOpenLink)(const char *url) = NULL;
+// Rust's join history hook: records that the call starting at start_ts (unix
+// seconds) was joined via url.
+static void (*gOnJoin)(int64_t start_ts, const char *url) = NULL;
+// The current preferences, pre-filled in the "Preferences…" dialog in field
+// order (calendar URL, ElevenLabs key, voice, minutes before); set via
+// tray_set_preferences, empty until then.
+static NSArray<NSString *> *gPreferences = nil;
+
+// The next call's link for the "Join" item (nil = no call, item disabled),
+// and the https link to open instead when no app handles a native-app link.
+// Set via tray_set_join; main thread only.
+static NSURL *gJoinURL = nil;
+static NSURL *gJoinFallbackURL = nil;
+
 // The "Dismiss" toggle, owned by the tray: Rust arms gDismissTarget each tick
 // (next call's start unix time; 0 = no call, item disabled) and a click flips
 // gDismissedTs between 0 and the target. Atomics: Rust polls off-main-thread.
 static _Atomic int64_t gDismissTarget = 0;
 static _Atomic int64_t gDismissedTs = 0;
+// The "Silence This Call" toggle: like a dismissal it targets the armed call
+// (gDismissTarget) and expires with it, but only mutes sound and speech.
+static _Atomic int64_t gSilencedTs = 0;
+// The "Pause Notifications" toggle: while set, Rust mutes every call's alerts
+// (the countdown keeps updating). Session-only, like the dismissal.
+static _Atomic bool gPaused = false;
 
 // Created once in tray_run on the main thread; live for the process lifetime.
 static NSStatusItem *gStatusItem = nil;
 // The "Dismiss" / "Revert dismiss" menu item; its title tracks gDismissedTs.
 static NSMenuItem *gDismissMenuItem = nil;
+// The "Join: <summary>" menu item; retitled by tray_set_join.
+static NSMenuItem *gJoinMenuItem = nil;
+// The "Upcoming" item; its submenu is rebuilt by tray_clear_upcoming /
+// tray_add_upcoming, and removed (disabling the item) while empty.
+static NSMenuItem *gUpcomingMenuItem = nil;
+
+// Opens a meeting link, or fallback (if any) when no app handles link's
+// scheme - e.g. a zoommtg:// link without Zoom installed. An http(s) link goes
+// to gOnOpenLink first, for a configured browser_command. on_opened, if not
+// nil, runs once the link has actually opened.
+static void open_link(NSURL *link, NSURL *fallback, void (^on_opened)(void)) {
+  NSWorkspace *ws = [NSWorkspace sharedWorkspace];
+  if (fallback != nil && [ws URLForApplicationToOpenURL:link] == nil) {
+    link = fallback;
+  }
+  NSString *scheme = link.scheme.lowercaseString;
+  if (([scheme isEqualToString:@"http"] || [scheme isEqualToString:@"https"]) && gOnOpenLink != NULL &&
+      gOnOpenLink(link.absoluteString.UTF8String)) {
+    if (on_opened != nil) {
+      on_opened();
+    }
+    return;
+  }
+  [ws openURL:link
+          configuration:[NSWorkspaceOpenConfiguration configuration]
+      completionHandler:^(NSRunningApplication *_Nullable app, NSError *_Nullable error) {
+        if (error == nil && on_opened != nil) {
+          on_opened();
+        }
+      }];
+}
 // Last raw countdown text from Rust; render() derives the display from it.
 static NSString *gTitle = @"...";
 // True while calendar fetches are failing (set via tray_set_warning); shown
 // as a warning triangle beside the countdown, replacing the idle logo.
 static bool gWarning = false;
+// Countdown colour from tray_set_urgency: 0 normal, 1 soon (orange),
+// 2 started (red).
+static int gUrgency = 0;
 // The stopwatch-lens logo (assets/tray-icon.png in Resources), shown instead
 // of the idle "..." text; nil outside a bundle, which falls back to "...".
 static NSImage *gIdleIcon = nil;
+// True when the calendar has no upcoming call at all (set via
+// tray_set_all_clear): the idle state shows a checkmark instead of the logo,
+// which then means "next call more than a few hours away".
+static bool gAllClear = false;
+// Fill of the optional progress ring beside the countdown, 0 to 1 (set via
+// tray_set_progress); negative = no ring.
+static double gProgress = -1;
+// Hover text describing the next call (set via tray_set_tooltip); nil until
+// the first tick.
+static NSString *gTooltip = nil;
+
+// A template image of the progress ring: a faint full circle with an arc
+// filled clockwise from 12 o'clock by fraction.
+static NSImage *progress_ring(double fraction) {
+  return [NSImage imageWithSize:NSMakeSize(14, 14)
+                        flipped:NO
+                 drawingHandler:^BOOL(NSRect rect) {
+                   NSRect circle = NSInsetRect(rect, 1.5, 1.5);
+                   NSPoint center = NSMakePoint(NSMidX(circle), NSMidY(circle));
+                   CGFloat radius = NSWidth(circle) / 2;
+                   NSBezierPath *track = [NSBezierPath bezierPathWithOvalInRect:circle];
+                   track.lineWidth = 2;
+                   [[NSColor colorWithWhite:0 alpha:0.3] setStroke];
+                   [track stroke];
+                   NSBezierPath *arc = [NSBezierPath bezierPath];
+                   [arc appendBezierPathWithArcWithCenter:center
+                                                   radius:radius
+                                               startAngle:90
+                                                 endAngle:90 - 360 * fraction
+                                                clockwise:YES];
+                   arc.lineWidth = 2;
+                   [[NSColor blackColor] setStroke];
+                   [arc stroke];
+                   return YES;
+                 }];
+}
 
 // Renders the status item and Dismiss menu item from (gTitle, gDismissedTs,
-// gWarning) — the one place display state is applied, called when any input
-// changes. One image slot, so priority: dismissed bell > warning > idle logo
+// gWarning, gUrgency, gAllClear, gProgress) — the one place display state is
+// applied, called when any input changes. One image slot, so priority:
+// dismissed bell > warning > all-clear checkmark > idle logo > progress ring
 // (a warning while dismissed still shows in the tooltip). Main thread only.
 static void render(void) {
   bool dismissed = atomic_load(&gDismissedTs) != 0;
@@ -54,17 +175,44 @@ static void render(void) {
         [NSImage imageWithSystemSymbolName:@"exclamationmark.triangle"
                   accessibilityDescription:@"calendar fetch failing"];
     gStatusItem.button.imagePosition = NSImageLeft;
+  } else if (idle && gAllClear) {
+    // nothing scheduled at all
+    gStatusItem.button.title = @"";
+    gStatusItem.button.image =
+        [NSImage imageWithSystemSymbolName:@"checkmark.circle"
+                  accessibilityDescription:@"no upcoming calls"];
+    gStatusItem.button.imagePosition = NSImageOnly;
   } else if (idle && gIdleIcon != nil) {
-    // no upcoming call: show the logo rather than "..."
+    // no call soon: show the logo rather than "..."
     gStatusItem.button.title = @"";
     gStatusItem.button.image = gIdleIcon;
     gStatusItem.button.imagePosition = NSImageOnly;
+  } else if (!idle && gProgress >= 0) {
+    gStatusItem.button.title = gTitle;
+    NSImage *ring = progress_ring(gProgress);
+    ring.template = YES;
+    gStatusItem.button.image = ring;
+    gStatusItem.button.imagePosition = NSImageLeft;
   } else {
     gStatusItem.button.title = gTitle;
     gStatusItem.button.image = nil;
   }
+  NSColor *color = gUrgency == 2 ? [NSColor systemRedColor]
+                   : gUrgency == 1 ? [NSColor systemOrangeColor]
+                                   : nil;
+  if (color != nil && gStatusItem.button.title.length > 0) {
+    // keep the menu bar font: an attributed title otherwise falls back to
+    // the system default size
+    gStatusItem.button.attributedTitle = [[NSAttributedString alloc]
+        initWithString:gStatusItem.button.title
+            attributes:@{
+              NSForegroundColorAttributeName : color,
+              NSFontAttributeName : [NSFont menuBarFontOfSize:0],
+            }];
+  }
   gStatusItem.button.toolTip =
-      gWarning ? @"Calendar requests are failing — see Logs" : nil;
+      gWarning ? [NSString stringWithFormat:@"Calendar requests are failing — see Logs\n%@", gTooltip ?: @""]
+               : gTooltip;
   gDismissMenuItem.title = dismissed ? @"Revert dismiss" : @"Dismiss";
 }
 
@@ -82,6 +230,24 @@ static void render(void) {
                   URLWithString:@"https://github.com/samuelcolvin/nextcall"]];
 }
 
+// Shows the version, config file and log file in a modal alert, for support
+// questions like "which nextcall.toml is it reading?", plus the day's
+// attendance. Its "GitHub" button opens the project repository.
+- (void)showAbout:(id)sender {
+  // an accessory app isn't frontmost: bring the alert in front of other apps
+  [NSApp activateIgnoringOtherApps:YES];
+  NSAlert *alert = [[NSAlert alloc] init];
+  alert.messageText = gVersion != nil ? [NSString stringWithFormat:@"Nextcall %@", gVersion] : @"Nextcall";
+  alert.informativeText =
+      [NSString stringWithFormat:@"Config: %@\nSearched: %@\nLog: %@\n\n%@", gConfigPath ?: @"not found",
+                                 gConfigSearch ?: @"", gLogPath ?: @"not set", gAttendance ?: @""];
+  [alert addButtonWithTitle:@"OK"];
+  [alert addButtonWithTitle:@"GitHub"];
+  if ([alert runModal] == NSAlertSecondButtonReturn) {
+    [self openGitHub:sender];
+  }
+}
+
 // Opens this run's log file in the default .log viewer (same as `open <path>`).
 // No-op until Rust has set the path via tray_set_log_path.
 - (void)openLog:(id)sender {
@@ -90,6 +256,167 @@ static void render(void) {
   }
 }
 
+// Asks Rust to refetch the calendar now. The callback only signals the
+// background loop, so it's safe to run on the main thread.
+- (void)refresh:(id)sender {
+  if (gOnRefresh != NULL) {
+    gOnRefresh();
+  }
+}
+
+// Opens the next call's link, like clicking its notification would.
+- (void)joinCall:(id)sender {
+  if (gJoinURL != nil) {
+    int64_t start_ts = atomic_load(&gDismissTarget);
+    NSString *url = gJoinURL.absoluteString;
+    // only a link that opened counts as a join
+    open_link(gJoinURL, gJoinFallbackURL, ^{
+      if (gOnJoin != NULL) {
+        gOnJoin(start_ts, url.UTF8String);
+      }
+    });
+  }
+}
+
+// Opens the link of a call in the "Upcoming" submenu, stored on the item as
+// @[url] or @[url, fallback].
+- (void)openUpcoming:(NSMenuItem *)sender {
+  NSArray<NSURL *> *links = sender.representedObject;
+  open_link(links[0], links.count > 1 ? links[1] : nil, nil);
+}
+
+// A right-aligned label for a row of the Preferences grid.
+static NSTextField *preferences_label(NSString *text) {
+  NSTextField *label = [NSTextField labelWithString:text];
+  label.alignment = NSTextAlignmentRight;
+  return label;
+}
+
+// The fields' trimmed values, in field order.
+static NSArray<NSString *> *preferences_values(NSArray<NSTextField *> *fields) {
+  NSMutableArray<NSString *> *values = [NSMutableArray array];
+  for (NSTextField *field in fields) {
+    [values addObject:[field.stringValue
+                          stringByTrimmingCharactersInSet:[NSCharacterSet whitespaceAndNewlineCharacterSet]]];
+  }
+  return values;
+}
+
+// Shows the settings in a modal alert with one field each and hands them to
+// Rust, which checks a changed calendar URL serves a calendar, saves them to
+// the config file and reloads. The check runs off the main thread (see
+// savePreferences:) while the alert shows a spinner, so the menu bar never
+// freezes. Settings that fail the check re-present the alert with the error
+// and a "Save Anyway" button (e.g. for offline users). Cancel does nothing.
+- (void)showPreferences:(id)sender {
+  if (gOnSavePreferences == NULL) {
+    return;
+  }
+  // an accessory app isn't frontmost: bring the alert in front of other apps
+  [NSApp activateIgnoringOtherApps:YES];
+  NSArray<NSString *> *values = gPreferences ?: @[ @"", @"", @"", @"" ];
+  NSString *failure = nil;
+  while (true) {
+    NSAlert *alert = [[NSAlert alloc] init];
+    alert.messageText = @"Preferences";
+    alert.informativeText = failure != nil ? [NSString stringWithFormat:@"Not saved: %@", failure]
+                                           : @"Saved to your nextcall.toml. Leave a field empty for the default.";
+    // Save and Save Anyway come here rather than ending the modal session,
+    // which savePreferences: does once the save is done
+    NSMutableArray<NSButton *> *saveButtons = [NSMutableArray arrayWithObject:[alert addButtonWithTitle:@"Save"]];
+    [alert addButtonWithTitle:@"Cancel"];
+    if (failure != nil) {
+      NSButton *saveAnyway = [alert addButtonWithTitle:@"Save Anyway"];
+      saveAnyway.tag = 1;
+      [saveButtons addObject:saveAnyway];
+    }
+    for (NSButton *button in saveButtons) {
+      button.target = self;
+      button.action = @selector(savePreferences:);
+    }
+    // edit the current (or rejected) values in place; placeholders show the
+    // defaults
+    NSTextField *url = [[NSTextField alloc] initWithFrame:NSMakeRect(0, 0, 320, 24)];
+    url.placeholderString = @"https://calendar.google.com/calendar/ical/.../basic.ics";
+    NSSecureTextField *key = [[NSSecureTextField alloc] initWithFrame:NSMakeRect(0, 0, 320, 24)];
+    key.placeholderString = @"none: use the built-in voice";
+    NSTextField *voice = [[NSTextField alloc] initWithFrame:NSMakeRect(0, 0, 320, 24)];
+    voice.placeholderString = @"system voice";
+    NSTextField *notifyBefore = [[NSTextField alloc] initWithFrame:NSMakeRect(0, 0, 320, 24)];
+    notifyBefore.placeholderString = @"no heads-up";
+    NSArray<NSTextField *> *fields = @[ url, key, voice, notifyBefore ];
+    for (NSUInteger i = 0; i < fields.count; i++) {
+      fields[i].stringValue = values[i];
+    }
+    // shown while a save is in progress
+    NSProgressIndicator *spinner = [[NSProgressIndicator alloc] initWithFrame:NSMakeRect(0, 0, 16, 16)];
+    spinner.style = NSProgressIndicatorStyleSpinning;
+    spinner.controlSize = NSControlSizeSmall;
+    spinner.displayedWhenStopped = NO;
+    NSTextField *progress = [NSTextField labelWithString:@"Checking the calendar URL…"];
+    progress.hidden = YES;
+    NSGridView *grid = [NSGridView gridViewWithViews:@[
+      @[ preferences_label(@"Calendar URL"), url ],
+      @[ preferences_label(@"ElevenLabs key"), key ],
+      @[ preferences_label(@"Built-in voice"), voice ],
+      @[ preferences_label(@"Minutes before"), notifyBefore ],
+      @[ spinner, progress ],
+    ]];
+    [grid cellForView:spinner].xPlacement = NSGridCellPlacementTrailing;
+    grid.rowSpacing = 8;
+    grid.frame = NSMakeRect(0, 0, 440, grid.fittingSize.height);
+    alert.accessoryView = grid;
+    alert.window.initialFirstResponder = url;
+    gPreferencesAlert = alert;
+    gPreferenceFields = fields;
+    gPreferencesSpinner = spinner;
+    gPreferencesProgress = progress;
+    gPreferencesFailure = nil;
+    NSModalResponse response = [alert runModal];
+    gPreferencesAlert = nil;
+    gPreferenceFields = nil;
+    gPreferencesSpinner = nil;
+    gPreferencesProgress = nil;
+    if (response == NSAlertSecondButtonReturn) {
+      return;
+    }
+    values = preferences_values(fields);
+    if (gPreferencesFailure == nil) {
+      return;
+    }
+    failure = gPreferencesFailure;
+  }
+}
+
+// Save / Save Anyway (tag 1) in the Preferences alert: disables the alert and
+// shows the spinner, then saves on a background queue, as a changed calendar
+// URL is fetched first, which can take up to the HTTP timeout. Back on the
+// main queue, the outcome is left in gPreferencesFailure and the modal
+// session ended, for showPreferences: to close or re-present the alert.
+- (void)savePreferences:(NSButton *)sender {
+  NSArray<NSString *> *values = preferences_values(gPreferenceFields);
+  bool force = sender.tag == 1;
+  for (NSTextField *field in gPreferenceFields) {
+    field.enabled = NO;
+  }
+  for (NSButton *button in gPreferencesAlert.buttons) {
+    button.enabled = NO;
+  }
+  gPreferencesProgress.hidden = NO;
+  [gPreferencesSpinner startAnimation:nil];
+  dispatch_async(dispatch_get_global_queue(QOS_CLASS_USER_INITIATED, 0), ^{
+    char error[512] = {0};
+    bool saved = gOnSavePreferences(values[0].UTF8String, values[1].UTF8String, values[2].UTF8String,
+                                    values[3].UTF8String, force, error, sizeof error);
+    NSString *failure = saved ? nil : ([NSString stringWithUTF8String:error] ?: @"unknown error");
+    dispatch_async(dispatch_get_main_queue(), ^{
+      gPreferencesFailure = failure;
+      // abortModal, unlike stopModal, ends the session from outside an event
+      [NSApp abortModal];
+    });
+  });
+}
+
 // Toggles the dismissed state for the armed call and re-renders immediately
 // (no waiting on the Rust loop, which may be mid-sleep for minutes). Rust
 // picks the new state up on its next tick, always before any alert fires.
@@ -102,11 +429,38 @@ static void render(void) {
   render();
 }
 
-// Greys out "Dismiss" when there is no upcoming call to act on (target 0).
+// Toggles "Silence This Call" for the armed call; its checkmark follows in
+// validateMenuItem. Rust picks it up on its next tick, like a dismissal.
+- (void)silenceCall:(id)sender {
+  int64_t target = atomic_load(&gDismissTarget);
+  if (target == 0) {
+    return;
+  }
+  atomic_store(&gSilencedTs, atomic_load(&gSilencedTs) == target ? 0 : target);
+}
+
+// Flips "Pause Notifications" and its checkmark; Rust picks it up on its next
+// tick, like a dismissal.
+- (void)togglePause:(NSMenuItem *)sender {
+  bool paused = !atomic_load(&gPaused);
+  atomic_store(&gPaused, paused);
+  sender.state = paused ? NSControlStateValueOn : NSControlStateValueOff;
+}
+
+// Greys out "Dismiss" and "Silence This Call" when there is no upcoming call
+// to act on (target 0), and checks the latter while it applies.
 - (BOOL)validateMenuItem:(NSMenuItem *)item {
   if (item.action == @selector(dismissCall:)) {
     return atomic_load(&gDismissTarget) != 0;
   }
+  if (item.action == @selector(silenceCall:)) {
+    int64_t target = atomic_load(&gDismissTarget);
+    item.state = target != 0 && atomic_load(&gSilencedTs) == target ? NSControlStateValueOn : NSControlStateValueOff;
+    return target != 0;
+  }
+  if (item.action == @selector(joinCall:)) {
+    return gJoinURL != nil;
+  }
   return YES;
 }
 
@@ -119,10 +473,23 @@ static NCMenuActions *gMenuActions = nil;
 static NSMenuItem *gStatusMenuItem = nil;
 
 // Creates the status item with a Quit menu and runs the AppKit event loop.
+// on_refresh is called (on the main thread) when "Refresh Now" is clicked,
+// on_save_preferences with the fields entered via "Preferences…", and
+// on_open_link with each http(s) link "Join" or "Upcoming" opens, and
+// on_join with the call "Join" opened (once its link has), for the join
+// history.
 // Must be called on the main thread; never returns ("Quit" terminates the
 // process via NSApp terminate:).
-void tray_run(void) {
+void tray_run(void (*on_refresh)(void),
+              bool (*on_save_preferences)(const char *ical_url, const char *eleven_labs_key,
+                                          const char *builtin_voice, const char *notify_before_minutes, bool force,
+                                          char *error, size_t error_len),
+              bool (*on_open_link)(const char *url), void (*on_join)(int64_t start_ts, const char *url)) {
   @autoreleasepool {
+    gOnRefresh = on_refresh;
+    gOnSavePreferences = on_save_preferences;
+    gOnOpenLink = on_open_link;
+    gOnJoin = on_join;
     NSApplication *app = [NSApplication sharedApplication];
     // Accessory: menu bar presence only, no Dock icon or app menu.
     [app setActivationPolicy:NSApplicationActivationPolicyAccessory];
@@ -143,23 +510,52 @@ void tray_run(void) {
                                                  action:nil
                                           keyEquivalent:@""];
     [menu addItem:gStatusMenuItem];
+    gJoinMenuItem = [[NSMenuItem alloc] initWithTitle:@"Join"
+                                               action:@selector(joinCall:)
+                                        keyEquivalent:@""];
+    gJoinMenuItem.target = gMenuActions;
+    [menu addItem:gJoinMenuItem];
+    // no action and no submenu until calls arrive: auto-disabled
+    gUpcomingMenuItem = [[NSMenuItem alloc] initWithTitle:@"Upcoming"
+                                                   action:nil
+                                            keyEquivalent:@""];
+    [menu addItem:gUpcomingMenuItem];
     gDismissMenuItem = [[NSMenuItem alloc] initWithTitle:@"Dismiss"
                                                   action:@selector(dismissCall:)
                                            keyEquivalent:@""];
     gDismissMenuItem.target = gMenuActions;
     [menu addItem:gDismissMenuItem];
+    NSMenuItem *silence = [[NSMenuItem alloc] initWithTitle:@"Silence This Call"
+                                                     action:@selector(silenceCall:)
+                                              keyEquivalent:@""];
+    silence.target = gMenuActions;
+    [menu addItem:silence];
+    NSMenuItem *pause = [[NSMenuItem alloc] initWithTitle:@"Pause Notifications"
+                                                   action:@selector(togglePause:)
+                                            keyEquivalent:@""];
+    pause.target = gMenuActions;
+    [menu addItem:pause];
+    NSMenuItem *refresh = [[NSMenuItem alloc] initWithTitle:@"Refresh Now"
+                                                     action:@selector(refresh:)
+                                              keyEquivalent:@""];
+    refresh.target = gMenuActions;
+    [menu addItem:refresh];
     [menu addItem:[NSMenuItem separatorItem]];
+    NSMenuItem *preferences = [[NSMenuItem alloc] initWithTitle:@"Preferences…"
+                                                         action:@selector(showPreferences:)
+                                                  keyEquivalent:@","];
+    preferences.target = gMenuActions;
+    [menu addItem:preferences];
     NSMenuItem *viewLog = [[NSMenuItem alloc] initWithTitle:@"View Log"
                                                      action:@selector(openLog:)
                                               keyEquivalent:@""];
     viewLog.target = gMenuActions;
     [menu addItem:viewLog];
-    NSMenuItem *github =
-        [[NSMenuItem alloc] initWithTitle:@"About Nextcall"
-                                   action:@selector(openGitHub:)
-                            keyEquivalent:@""];
-    github.target = gMenuActions;
-    [menu addItem:github];
+    NSMenuItem *about = [[NSMenuItem alloc] initWithTitle:@"About Nextcall"
+                                                   action:@selector(showAbout:)
+                                            keyEquivalent:@""];
+    about.target = gMenuActions;
+    [menu addItem:about];
     [menu addItem:[NSMenuItem separatorItem]];
     // nil target: the responder chain routes terminate: to NSApp.
     [menu addItemWithTitle:@"Quit Nextcall"
@@ -173,14 +569,17 @@ void tray_run(void) {
 
 // Updates the status item text. Thread-safe: hops to the main queue, so it
 // may be called from Rust worker threads (and before tray_run - the update is
-// applied once the loop starts).
+// applied once the loop starts). Called every tick, so it only re-renders on a
+// change: rebuilding the attributed title and images each time is wasted work.
 void tray_set_title(const char *title) {
   @autoreleasepool {
     // Copy to NSString now: the char* is only valid for this call.
     NSString *text = @(title);
     dispatch_async(dispatch_get_main_queue(), ^{
-      gTitle = text;
-      render();
+      if (![gTitle isEqualToString:text]) {
+        gTitle = text;
+        render();
+      }
     });
   }
 }
@@ -196,9 +595,24 @@ void tray_set_status(const char *status) {
   }
 }
 
+// Sets the status item's hover text. Thread-safe, same main-queue rules as
+// tray_set_title; only re-renders on a change.
+void tray_set_tooltip(const char *tooltip) {
+  @autoreleasepool {
+    NSString *text = @(tooltip);
+    dispatch_async(dispatch_get_main_queue(), ^{
+      if (![gTooltip isEqualToString:text]) {
+        gTooltip = text;
+        render();
+      }
+    });
+  }
+}
+
 // Arms the "Dismiss" menu item with the current call's start unix time
 // (0 = no call: the item is disabled), clearing a dismissal that no longer
-// refers to it — how a dismissal expires when the next call changes. Called
+// refers to it — how a dismissal expires when the next call changes. The
+// "Silence This Call" item shares the target and expires the same way. Called
 // from Rust every tick.
 void tray_set_dismiss_target(int64_t start_ts) {
   atomic_store(&gDismissTarget, start_ts);
@@ -207,11 +621,108 @@ void tray_set_dismiss_target(int64_t start_ts) {
     // CAS: only clear the stale value we saw, never a racing fresh click
     atomic_compare_exchange_strong(&gDismissedTs, &stale, 0);
   }
+  int64_t stale_silenced = atomic_load(&gSilencedTs);
+  if (stale_silenced != 0 && stale_silenced != start_ts) {
+    atomic_compare_exchange_strong(&gSilencedTs, &stale_silenced, 0);
+  }
   dispatch_async(dispatch_get_main_queue(), ^{
     render();
   });
 }
 
+// Records the current preferences (empty strings = unset), pre-filled when
+// the "Preferences…" dialog opens. Thread-safe, same main-queue rules as
+// tray_set_title; called from Rust at startup and after each config reload.
+void tray_set_preferences(const char *ical_url, const char *eleven_labs_key, const char *builtin_voice,
+                          const char *notify_before_minutes) {
+  @autoreleasepool {
+    NSArray<NSString *> *values = @[ @(ical_url), @(eleven_labs_key), @(builtin_voice), @(notify_before_minutes) ];
+    dispatch_async(dispatch_get_main_queue(), ^{
+      gPreferences = values;
+    });
+  }
+}
+
+// Records what "About Nextcall" shows: the app version, the config file's
+// path (NULL = none found) and the search order that found it. Thread-safe,
+// same main-queue rules as tray_set_title; called from Rust at startup and on
+// reload.
+void tray_set_about(const char *version, const char *config_path, const char *config_search) {
+  @autoreleasepool {
+    NSString *versionText = @(version);
+    NSString *pathText = config_path != NULL ? @(config_path) : nil;
+    NSString *searchText = @(config_search);
+    dispatch_async(dispatch_get_main_queue(), ^{
+      gVersion = versionText;
+      gConfigPath = pathText;
+      gConfigSearch = searchText;
+    });
+  }
+}
+
+// Records the attendance line "About Nextcall" shows. Thread-safe, same
+// main-queue rules as tray_set_title; called from Rust when it changes.
+void tray_set_attendance(const char *attendance) {
+  @autoreleasepool {
+    NSString *text = @(attendance);
+    dispatch_async(dispatch_get_main_queue(), ^{
+      gAttendance = text;
+    });
+  }
+}
+
+// Arms the "Join" item with the next call: its summary (shown as "Join:
+// <summary>") and link, plus an optional https fallback_url for a native-app
+// url. A NULL url disables the item. Thread-safe, same main-queue rules as
+// tray_set_title; called from Rust every tick.
+void tray_set_join(const char *summary, const char *url, const char *fallback_url) {
+  @autoreleasepool {
+    NSString *title = url != NULL ? [NSString stringWithFormat:@"Join: %@", @(summary)] : @"Join";
+    NSURL *joinURL = url != NULL ? [NSURL URLWithString:@(url)] : nil;
+    NSURL *fallback = fallback_url != NULL ? [NSURL URLWithString:@(fallback_url)] : nil;
+    dispatch_async(dispatch_get_main_queue(), ^{
+      gJoinURL = joinURL;
+      gJoinFallbackURL = fallback;
+      gJoinMenuItem.title = title;
+    });
+  }
+}
+
+// Empties the "Upcoming" submenu (disabling the item) ahead of a run of
+// tray_add_upcoming calls. Thread-safe, same main-queue rules as
+// tray_set_title; the serial main queue keeps clear and adds in order.
+void tray_clear_upcoming(void) {
+  dispatch_async(dispatch_get_main_queue(), ^{
+    gUpcomingMenuItem.submenu = nil;
+  });
+}
+
+// Appends a call to the "Upcoming" submenu: a label (start time and summary)
+// opening url, or fallback_url (may be NULL) when no app handles url.
+// Thread-safe, same main-queue rules as tray_set_title.
+void tray_add_upcoming(const char *label, const char *url, const char *fallback_url) {
+  @autoreleasepool {
+    NSString *title = @(label);
+    NSURL *link = [NSURL URLWithString:@(url)];
+    NSURL *fallback = fallback_url != NULL ? [NSURL URLWithString:@(fallback_url)] : nil;
+    if (link == nil) {
+      return;
+    }
+    NSArray<NSURL *> *links = fallback != nil ? @[ link, fallback ] : @[ link ];
+    dispatch_async(dispatch_get_main_queue(), ^{
+      if (gUpcomingMenuItem.submenu == nil) {
+        gUpcomingMenuItem.submenu = [[NSMenu alloc] initWithTitle:@"Upcoming"];
+      }
+      NSMenuItem *item = [[NSMenuItem alloc] initWithTitle:title
+                                                    action:@selector(openUpcoming:)
+                                             keyEquivalent:@""];
+      item.target = gMenuActions;
+      item.representedObject = links;
+      [gUpcomingMenuItem.submenu addItem:item];
+    });
+  }
+}
+
 // Shows/hides the fetch-failure warning triangle. Thread-safe, same
 // main-queue rules as tray_set_title; called from Rust every fetch cycle, so
 // it only re-renders on an actual state change.
@@ -224,11 +735,52 @@ void tray_set_warning(bool warning) {
   });
 }
 
+// Shows the all-clear checkmark in place of the idle logo while there is no
+// upcoming call. Thread-safe, same main-queue rules as tray_set_title; called
+// from Rust every tick, so it only re-renders on a change.
+void tray_set_all_clear(bool all_clear) {
+  dispatch_async(dispatch_get_main_queue(), ^{
+    if (gAllClear != all_clear) {
+      gAllClear = all_clear;
+      render();
+    }
+  });
+}
+
+// Sets the progress ring's fill (0 to 1; negative hides it). Thread-safe,
+// same main-queue rules as tray_set_title; only re-renders on a change.
+void tray_set_progress(double progress) {
+  dispatch_async(dispatch_get_main_queue(), ^{
+    if (gProgress != progress) {
+      gProgress = progress;
+      render();
+    }
+  });
+}
+
+// Sets the countdown colour (0 normal, 1 soon, 2 started). Thread-safe, same
+// main-queue rules as tray_set_title; only re-renders on a change.
+void tray_set_urgency(int urgency) {
+  dispatch_async(dispatch_get_main_queue(), ^{
+    if (gUrgency != urgency) {
+      gUrgency = urgency;
+      render();
+    }
+  });
+}
+
 // The start unix time of the call the user dismissed via the menu (0 = none).
 // Polled from Rust each tick and matched against the current next call there
 // before suppressing alerts, so a stale value is harmless.
 int64_t tray_dismissed_ts(void) { return atomic_load(&gDismissedTs); }
 
+// The start unix time of the call silenced via the menu (0 = none); polled
+// and matched like tray_dismissed_ts.
+int64_t tray_silenced_ts(void) { return atomic_load(&gSilencedTs); }
+
+// Whether "Pause Notifications" is checked. Polled from Rust each tick.
+bool tray_paused(void) { return atomic_load(&gPaused); }
+
 // Records the path opened by the "View Log" menu item. Thread-safe, same
 // main-queue rules as tray_set_title; called once from Rust at startup.
 void tray_set_log_path(const char *path) {
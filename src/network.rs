@@ -0,0 +1,52 @@
+//! Network reachability, backed by the ObjC implementation in
+//! `src/native/network.m` (Network framework path monitor).
+//!
+//! The monitor reports every path update; this module reduces them to
+//! offline -> online transitions and wakes the background loop with
+//! [`Wake::NetworkUp`], so a failing calendar fetch recovers immediately.
+
+use crate::wake::{self, Wake};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+unsafe extern "C" {
+    fn network_watch(on_change: extern "C" fn(connected: bool));
+}
+
+/// The last reported state. Starts connected so the monitor's initial
+/// "online" report isn't mistaken for a reconnection.
+static CONNECTED: AtomicBool = AtomicBool::new(true);
+
+/// Starts the path monitor. Call once, after [`wake::channel`].
+pub fn watch() {
+    unsafe { network_watch(on_change) }
+}
+
+/// Path-update callback, invoked from a background queue by the monitor.
+extern "C" fn on_change(connected: bool) {
+    let was_connected = CONNECTED.swap(connected, Ordering::SeqCst);
+    if connected && !was_connected {
+        wake::send(Wake::NetworkUp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn reconnection_wakes_the_loop_once() {
+        let wakes = wake::channel();
+        // initial online report and repeats: no wake
+        on_change(true);
+        on_change(true);
+        assert!(wakes.try_recv().is_err());
+        // offline -> online transition drives exactly one refresh
+        on_change(false);
+        on_change(false);
+        on_change(true);
+        on_change(true);
+        assert_eq!(wakes.recv_timeout(Duration::from_secs(1)), Ok(Wake::NetworkUp));
+        assert!(wakes.try_recv().is_err());
+    }
+}
@@ -5,14 +5,41 @@
 //! boundary. Foot-gun: notifications require running from a signed `.app`
 //! bundle with a `CFBundleIdentifier` - they do nothing from a bare binary.
 
-use std::ffi::{CString, c_char};
+use crate::{browser, history};
+use chrono::{DateTime, Utc};
+use std::ffi::{CStr, CString, c_char};
 use std::ptr;
+use std::sync::Mutex;
+use tracing::{info, warn};
 
 unsafe extern "C" {
-    fn notifications_startup();
-    fn notifications_send(title: *const c_char, subtitle: *const c_char, body: *const c_char, url: *const c_char);
+    fn notifications_startup(
+        on_snooze: extern "C" fn(start_ts: i64),
+        on_open_link: extern "C" fn(url: *const c_char) -> bool,
+        on_join: extern "C" fn(start_ts: i64, url: *const c_char),
+        on_opened: extern "C" fn(url: *const c_char, error: *const c_char),
+    );
+    fn notifications_set_snooze_minutes(snooze_minutes: u32);
+    fn notifications_clear_delivered();
+    fn notifications_send(
+        title: *const c_char,
+        subtitle: *const c_char,
+        body: *const c_char,
+        url: *const c_char,
+        fallback_url: *const c_char,
+        start_ts: i64,
+        sound: bool,
+        passive: bool,
+    );
 }
 
+/// Default `snooze_minutes`: how long the Snooze button mutes a call.
+pub const DEFAULT_SNOOZE_MINUTES: u32 = 5;
+
+/// The latest Snooze press: the snoozed call's start (unix seconds) and when
+/// it was pressed.
+static SNOOZE: Mutex<Option<(i64, DateTime<Utc>)>> = Mutex::new(None);
+
 /// Converts a Rust string for the C boundary, stripping interior NUL bytes
 /// (which are impossible in real calendar data but must not cause a panic).
 fn cstring(s: &str) -> CString {
@@ -20,25 +47,99 @@ fn cstring(s: &str) -> CString {
 }
 
 /// Installs the notification delegate, requests permission, and registers the
-/// "Join" action category. Must be called once at startup, before [`send`].
+/// "Join" / "Copy Link" / "Snooze" action category, with link clicks going
+/// through [`browser::on_open_link`], recorded by [`history::on_join`] and
+/// their outcome logged.
+/// Must be called once at startup, before [`send`].
 pub fn startup() {
-    unsafe { notifications_startup() }
+    unsafe { notifications_startup(on_snooze, browser::on_open_link, history::on_join, on_opened) }
+    set_snooze_minutes(DEFAULT_SNOOZE_MINUTES);
+}
+
+/// (Re-)registers the action category with this length on the Snooze button
+/// ("Snooze 5 min"); call when the config is loaded. The snooze itself is
+/// timed by the caller of [`snoozed`].
+pub fn set_snooze_minutes(minutes: u32) {
+    unsafe { notifications_set_snooze_minutes(minutes) }
+}
+
+/// Called by the ObjC delegate (on a background queue) when a notification's
+/// Snooze button is pressed.
+extern "C" fn on_snooze(start_ts: i64) {
+    info!("snooze pressed for call starting at {start_ts}");
+    *SNOOZE.lock().unwrap_or_else(|e| e.into_inner()) = Some((start_ts, Utc::now()));
+}
+
+/// Called by the ObjC delegate (on any thread) once a notification's link has
+/// been handed to `NSWorkspace`, with a NULL `error` if it opened - or
+/// without trying, when the link isn't a valid URL.
+extern "C" fn on_opened(url: *const c_char, error: *const c_char) {
+    let url = unsafe { CStr::from_ptr(url) }.to_string_lossy();
+    if error.is_null() {
+        info!("opened {url} from a notification");
+    } else {
+        let error = unsafe { CStr::from_ptr(error) }.to_string_lossy();
+        warn!("failed to open {url:?} from a notification: {error}");
+    }
+}
+
+/// The latest Snooze press: the snoozed call's start (unix seconds) and when
+/// it was pressed. The caller must match it against the current next call,
+/// as with the tray's dismissal. Thread-safe.
+pub fn snoozed() -> Option<(i64, DateTime<Utc>)> {
+    *SNOOZE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Removes our delivered notifications from Notification Center, e.g. the
+/// reminders left behind once the user has joined the call. Safe to call from
+/// any thread.
+pub fn clear_delivered() {
+    unsafe { notifications_clear_delivered() }
+}
+
+/// How a notification gets the user's attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interruption {
+    /// An active banner with the "Blow" sound.
+    Sound,
+    /// An active banner without sound, e.g. for a silenced call.
+    Silent,
+    /// Informational: silent, at the passive interruption level (Notification
+    /// Center only), and leaving the delivered notifications in place.
+    Passive,
 }
 
-/// Sends a system notification immediately (with the "Blow" sound and active
-/// interruption level). If `url` is given, the notification gets a "Join"
-/// button and any click on it opens the link. Safe to call from any thread.
-pub fn send(title: &str, subtitle: Option<&str>, body: &str, url: Option<&str>) {
+/// Sends a system notification immediately, as `interruption` says; all but
+/// an [`Interruption::Passive`] one replace any delivered ones. If `url` is
+/// given, the notification gets a "Join" button and any click on it opens the
+/// link - or `fallback_url` when no app handles `url` (e.g. a `zoommtg://`
+/// link without Zoom installed). Its "Copy Link" button copies `fallback_url`
+/// if given, else `url`, and its "Snooze" button records `start` (the call's
+/// start) for [`snoozed`]. Safe to call from any thread.
+pub fn send(
+    title: &str,
+    subtitle: Option<&str>,
+    body: &str,
+    url: Option<&str>,
+    fallback_url: Option<&str>,
+    start: Option<DateTime<Utc>>,
+    interruption: Interruption,
+) {
     let title = cstring(title);
     let subtitle = subtitle.map(cstring);
     let body = cstring(body);
     let url = url.map(cstring);
+    let fallback_url = fallback_url.map(cstring);
     unsafe {
         notifications_send(
             title.as_ptr(),
             subtitle.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
             body.as_ptr(),
             url.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            fallback_url.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            start.map_or(0, |t| t.timestamp()),
+            interruption == Interruption::Sound,
+            interruption == Interruption::Passive,
         )
     }
 }
@@ -0,0 +1,14 @@
+//! Power-source detection, backed by the implementation in
+//! `src/native/power.m` (IOKit power sources API).
+//!
+//! Lets the idle poll stretch out on battery (`battery_check_interval_secs`).
+
+unsafe extern "C" {
+    fn power_on_battery() -> bool;
+}
+
+/// Returns true if the Mac is running on battery power. Cheap enough to call
+/// every tick.
+pub fn on_battery() -> bool {
+    unsafe { power_on_battery() }
+}
@@ -1,15 +1,23 @@
-use anyhow::Result as AnyhowResult;
+use anyhow::{Context, Result as AnyhowResult};
 use bytes::Bytes;
 use regex::Regex;
-use rodio::OutputStreamBuilder;
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+use rodio::buffer::SamplesBuffer;
+use rodio::{Decoder, OutputStreamBuilder, Sink};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
 use std::io::{BufReader, Cursor};
-use std::process::Command;
-use std::sync::LazyLock;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{LazyLock, Mutex, PoisonError};
 use std::thread;
-use std::time::Duration;
-use tracing::error;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, warn};
 
 use crate::camera;
+use crate::config::{self, Config, OneOrMany};
 
 /// How often playback checks the camera so an announcement is cut short the
 /// moment the user joins the call — long titles must not talk over a live
@@ -53,39 +61,112 @@ pub fn tts_friendly(summary: &str) -> String {
     text
 }
 
+/// Held for the duration of each utterance, so overlapping announcements
+/// queue up rather than talk over each other.
+static SPEAKING: Mutex<()> = Mutex::new(());
+
+/// Speaks `text` on a background thread and returns immediately, so the
+/// caller (the main loop) is never stalled by synthesis or playback.
+/// Utterances play one at a time, in order; errors are logged. A non-zero
+/// `delay` is waited out first, after which nothing is said if the user has
+/// joined a call meanwhile ([`camera::in_call`]).
+pub fn say(text: &str, delay: Duration, config: &Config) {
+    let text = text.to_string();
+    let config = config.clone();
+    thread::spawn(move || {
+        if !delay.is_zero() {
+            thread::sleep(delay);
+            if camera::in_call(&config) {
+                info!("joined within {delay:?}, not speaking {text:?}");
+                return;
+            }
+        }
+        let _speaking = SPEAKING.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(err) = say_blocking(&text, &config) {
+            error!("failed to speak {text:?}: {err}");
+        }
+    });
+}
+
 /// Speaks `text`, via ElevenLabs when a key is configured, else the macOS
 /// `say` command. Blocks until playback finishes — or is cut short because the
 /// camera came on, i.e. the user joined the call mid-announcement.
-pub fn say(text: &str, eleven_labs_key: Option<&str>) -> AnyhowResult<()> {
-    if let Some(api_key) = eleven_labs_key {
-        say_eleven_labs(text, api_key)
+pub fn say_blocking(text: &str, config: &Config) -> AnyhowResult<()> {
+    if let Some(api_key) = config.eleven_labs_key.as_deref() {
+        say_eleven_labs(text, api_key, config)
     } else {
-        say_builtin(text)
+        say_builtin(text, config.builtin_voice.as_deref())
     }
 }
 
-/// ElevenLabs TTS played through rodio; falls back to [`say_builtin`] if the
-/// API request fails. Playback stops early if the camera becomes active.
-fn say_eleven_labs(text: &str, api_key: &str) -> AnyhowResult<()> {
-    // Generate MP3 using ElevenLabs API
-    let audio_bytes = match eleven_labs_request(text, api_key) {
-        Ok(bytes) => bytes,
+/// ElevenLabs TTS played through rodio, trying each `eleven_labs_voice_id` in
+/// turn; falls back to [`say_builtin`] if every voice's request fails or no
+/// audio output device can be opened, or without trying while a quota
+/// cooldown (see [`start_cooldown`]) is in force.
+/// The quota is the key's, so one starting also ends the walk through the
+/// voices. Playback stops early if the camera becomes active; the output
+/// stream is owned here, so it outlives playback.
+fn say_eleven_labs(text: &str, api_key: &str, config: &Config) -> AnyhowResult<()> {
+    let voices = config.eleven_labs_voice_id.as_ref().map(OneOrMany::as_slice);
+    let voices: Vec<&str> = voices.map_or(vec![VOICE_ID], |voices| voices.iter().map(String::as_str).collect());
+    let model = config.eleven_labs_model.as_deref().unwrap_or(MODEL_ID);
+    let format = TtsFormat::new(config);
+    let max_disk_bytes = config.tts_cache_mb.unwrap_or(DEFAULT_TTS_CACHE_MB) * 1024 * 1024;
+    if in_cooldown(api_key, Instant::now()) {
+        return say_builtin(text, config.builtin_voice.as_deref());
+    }
+    let api = match ElevenLabs::new(config) {
+        Ok(api) => api,
         Err(err) => {
-            error!("ElevenLabs API request failed, falling back to built-in: {}", err);
-            return say_builtin(text);
+            error!("failed to set up the ElevenLabs client, falling back to built-in: {err}");
+            return say_builtin(text, config.builtin_voice.as_deref());
+        }
+    };
+    // Generate audio using ElevenLabs API, unless it's cached
+    let mut audio_bytes = None;
+    for voice_id in voices {
+        match cached_request(&api, text, api_key, voice_id, model, format, max_disk_bytes) {
+            Ok(bytes) => {
+                audio_bytes = Some(bytes);
+                break;
+            }
+            Err(err) => warn!("ElevenLabs request with voice {voice_id:?} failed: {err}"),
         }
+        if in_cooldown(api_key, Instant::now()) {
+            break;
+        }
+    }
+    let Some(audio_bytes) = audio_bytes else {
+        error!("ElevenLabs API request failed, falling back to built-in");
+        return say_builtin(text, config.builtin_voice.as_deref());
     };
 
-    // Create output stream
-    let mut stream_handle = OutputStreamBuilder::open_default_stream()?;
+    // Create output stream; without an output device (e.g. a remote session)
+    // `say` may still manage, or at worst fails quietly
+    let mut stream_handle = match OutputStreamBuilder::open_default_stream() {
+        Ok(stream_handle) => stream_handle,
+        Err(err) => {
+            warn!("no audio output for ElevenLabs speech, falling back to built-in: {err}");
+            return say_builtin(text, config.builtin_voice.as_deref());
+        }
+    };
     stream_handle.log_on_drop(false);
 
-    // Use the audio bytes directly from memory via Cursor
-    let cursor = Cursor::new(audio_bytes);
-    let source = BufReader::new(cursor);
-
-    // Play the audio, watching the camera so joining the call cuts speech short.
-    let sink = rodio::play(stream_handle.mixer(), source)?;
+    // Play the audio at the configured volume, watching the camera so joining
+    // the call cuts speech short.
+    let sink = Sink::connect_new(stream_handle.mixer());
+    sink.set_volume(tts_volume(config));
+    let decode_start = Instant::now();
+    match format {
+        // Use the audio bytes directly from memory via Cursor
+        TtsFormat::Mp3 => sink.append(Decoder::new(BufReader::new(Cursor::new(audio_bytes)))?),
+        TtsFormat::Pcm => sink.append(SamplesBuffer::new(1, PCM_SAMPLE_RATE, pcm_samples(&audio_bytes))),
+    }
+    debug!(
+        "{} audio queued for playback in {:?}",
+        format.output_format(),
+        decode_start.elapsed()
+    );
     while !sink.empty() {
         thread::sleep(CAMERA_POLL);
         if camera::camera_active() {
@@ -96,17 +177,215 @@ fn say_eleven_labs(text: &str, api_key: &str) -> AnyhowResult<()> {
     Ok(())
 }
 
-/// Built-in fallback via the macOS `say` command; the process is killed if the
-/// camera becomes active mid-utterance.
-fn say_builtin(text: &str) -> AnyhowResult<()> {
-    let mut child = Command::new("say").arg("-v").arg("Moira").arg(text).spawn()?;
-    while child.try_wait()?.is_none() {
+/// The audio encoding requested from ElevenLabs (`tts_format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum TtsFormat {
+    /// `mp3_44100_128`, decoded by rodio; the default.
+    Mp3,
+    /// `pcm_44100`: raw samples, larger but playable without decoding.
+    Pcm,
+}
+
+/// Sample rate of [`TtsFormat::Pcm`] audio, in Hz.
+const PCM_SAMPLE_RATE: u32 = 44_100;
+
+impl TtsFormat {
+    /// From `tts_format`; anything but `pcm_44100` is MP3.
+    fn new(config: &Config) -> Self {
+        match config.tts_format.as_deref() {
+            Some("pcm_44100") => Self::Pcm,
+            _ => Self::Mp3,
+        }
+    }
+
+    /// The API's `output_format` query value.
+    fn output_format(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3_44100_128",
+            Self::Pcm => "pcm_44100",
+        }
+    }
+
+    /// Extension of disk cache entries in this format.
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::Pcm => "pcm",
+        }
+    }
+}
+
+/// ElevenLabs PCM audio - mono, signed 16-bit little-endian - as rodio samples.
+fn pcm_samples(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| f32::from(i16::from_le_bytes([pair[0], pair[1]])) / 32768.0)
+        .collect()
+}
+
+/// The ElevenLabs API used when `eleven_labs_base_url` is unset.
+const ELEVEN_LABS_BASE_URL: &str = "https://api.elevenlabs.io";
+
+/// Where ElevenLabs requests go, and the HTTP client that sends them.
+struct ElevenLabs {
+    client: reqwest::blocking::Client,
+    /// `eleven_labs_base_url`, without a trailing slash.
+    base_url: String,
+}
+
+impl ElevenLabs {
+    /// The API from `config`, its client going through the `proxy` setting
+    /// if there is one.
+    fn new(config: &Config) -> reqwest::Result<Self> {
+        let mut builder = reqwest::blocking::Client::builder().timeout(Duration::from_secs(10));
+        if let Some(proxy) = config.proxy()? {
+            builder = builder.proxy(proxy);
+        }
+        let base_url = config.eleven_labs_base_url.as_deref().unwrap_or(ELEVEN_LABS_BASE_URL);
+        Ok(Self {
+            client: builder.build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+/// `tts_volume` clamped to 0.0-1.0 (a typo like 50 must not blast at 50x;
+/// NaN plays at full volume like unset).
+fn tts_volume(config: &Config) -> f32 {
+    match config.tts_volume {
+        Some(volume) if !volume.is_nan() => volume.clamp(0.0, 1.0),
+        _ => 1.0,
+    }
+}
+
+/// The built-in speech command: macOS `say`, else `espeak`, which takes the
+/// same `-v <voice> <text>` arguments.
+#[cfg(target_os = "macos")]
+const SAY_COMMAND: &str = "say";
+#[cfg(not(target_os = "macos"))]
+const SAY_COMMAND: &str = "espeak";
+
+/// Built-in fallback via the [`SAY_COMMAND`], in `voice` or the system
+/// default voice; the process is killed if the camera becomes active
+/// mid-utterance. A failing `say` (e.g. an unknown voice) is logged, not
+/// returned: there's nothing further to fall back to.
+fn say_builtin(text: &str, voice: Option<&str>) -> AnyhowResult<()> {
+    let mut command = Command::new(SAY_COMMAND);
+    if let Some(voice) = voice {
+        command.arg("-v").arg(voice);
+    }
+    let mut child = command
+        .arg(text)
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run {SAY_COMMAND}"))?;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
         thread::sleep(CAMERA_POLL);
         if camera::camera_active() {
             let _ = child.kill();
             // Reap the killed process so it doesn't linger as a zombie.
             let _ = child.wait();
-            break;
+            return Ok(());
+        }
+    };
+    if !status.success() {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        error!("{SAY_COMMAND} failed ({status}), voice {voice:?}: {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// Default bound on the on-disk TTS cache, in MiB.
+const DEFAULT_TTS_CACHE_MB: u64 = 50;
+
+/// Past this many entries the in-memory cache is cleared: announcements
+/// repeat within a call's alert window, not across days.
+const MEMORY_CACHE_ENTRIES: usize = 64;
+
+/// Synthesized audio by format and [`cache_key`], in front of the disk cache.
+static MEMORY_CACHE: LazyLock<Mutex<HashMap<(TtsFormat, u64), Bytes>>> = LazyLock::new(Mutex::default);
+
+/// [`request_with_retries`] behind an in-memory and an on-disk cache
+/// (`~/.nextcall-tts-cache/`, bounded to `max_disk_bytes`, oldest evicted
+/// first; 0 disables it), so repeated phrases don't spend API credits.
+fn cached_request(
+    api: &ElevenLabs,
+    text: &str,
+    api_key: &str,
+    voice_id: &str,
+    model: &str,
+    format: TtsFormat,
+    max_disk_bytes: u64,
+) -> AnyhowResult<Bytes> {
+    let key = (format, cache_key(voice_id, model, text));
+    if let Some(bytes) = MEMORY_CACHE.lock().unwrap_or_else(PoisonError::into_inner).get(&key) {
+        return Ok(bytes.clone());
+    }
+    let disk_dir = config::home()
+        .ok()
+        .filter(|_| max_disk_bytes > 0)
+        .map(|home| PathBuf::from(home).join(".nextcall-tts-cache"));
+    let path = disk_dir
+        .as_ref()
+        .map(|dir| dir.join(format!("{:016x}.{}", key.1, format.extension())));
+    let bytes = match path.as_ref().and_then(|path| fs::read(path).ok()) {
+        Some(data) => Bytes::from(data),
+        None => {
+            let bytes = request_with_retries(api, text, api_key, voice_id, model, format)?;
+            if let (Some(dir), Some(path)) = (&disk_dir, &path)
+                && let Err(err) = save_to_disk(dir, path, &bytes, max_disk_bytes)
+            {
+                warn!("failed to write TTS cache: {err}");
+            }
+            bytes
+        }
+    };
+    let mut memory = MEMORY_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    if memory.len() >= MEMORY_CACHE_ENTRIES {
+        memory.clear();
+    }
+    memory.insert(key, bytes.clone());
+    Ok(bytes)
+}
+
+/// FNV-1a hash of the request: stable across runs and Rust versions (unlike
+/// `DefaultHasher`), so disk entries stay valid after a restart or upgrade.
+fn cache_key(voice_id: &str, model: &str, text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    // NUL separators keep ("ab", "c") and ("a", "bc") apart
+    for byte in [voice_id, model, text].join("\0").bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Writes `bytes` to `path` in the cache `dir`, then evicts the oldest
+/// entries until the directory fits in `max_bytes`.
+fn save_to_disk(dir: &Path, path: &Path, bytes: &[u8], max_bytes: u64) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(path, bytes)?;
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() {
+            entries.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+    }
+    // newest first: keep entries while they fit, delete the rest
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.0));
+    let mut total = 0;
+    for (_, len, entry_path) in entries {
+        total += len;
+        if total > max_bytes && entry_path != path {
+            fs::remove_file(entry_path)?;
         }
     }
     Ok(())
@@ -114,31 +393,338 @@ fn say_builtin(text: &str) -> AnyhowResult<()> {
 
 // male britsh
 const VOICE_ID: &str = "JBFqnCBsd6RMkjVDRZzb";
+const MODEL_ID: &str = "eleven_multilingual_v2";
+
+/// Attempts at an ElevenLabs request before giving up (and falling back).
+const ELEVEN_LABS_ATTEMPTS: u32 = 3;
+
+/// Backoff before the first retry, doubled before each further one.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// How long the API is left alone after a 401 or quota error whose response
+/// doesn't say (via `Retry-After`).
+const QUOTA_COOLDOWN: Duration = Duration::from_secs(60 * 60);
+
+/// The API key whose requests are paused after a 401 or quota error, and
+/// until when.
+static COOLDOWN: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+
+/// Why an ElevenLabs request failed, and so whether it's worth repeating.
+#[derive(Debug)]
+enum RequestError {
+    /// A timeout, connection failure, 429 or 5xx: likely to pass on retry.
+    Transient(anyhow::Error),
+    /// The key was rejected or its quota is used up: no use asking again
+    /// until `retry_after` (from the `Retry-After` header) if given, else
+    /// for [`QUOTA_COOLDOWN`].
+    Quota {
+        err: anyhow::Error,
+        retry_after: Option<Duration>,
+    },
+    /// Anything else, e.g. a bad voice id.
+    Permanent(anyhow::Error),
+}
+
+impl RequestError {
+    /// The underlying error, whatever its kind.
+    fn into_inner(self) -> anyhow::Error {
+        match self {
+            Self::Transient(err) | Self::Quota { err, .. } | Self::Permanent(err) => err,
+        }
+    }
+}
+
+impl From<reqwest::Error> for RequestError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() || err.is_connect() {
+            Self::Transient(err.into())
+        } else {
+            Self::Permanent(err.into())
+        }
+    }
+}
+
+/// [`eleven_labs_request`], retried with exponential backoff and jitter while
+/// it fails transiently, up to [`ELEVEN_LABS_ATTEMPTS`] times. A quota error
+/// starts a cooldown for `api_key`.
+fn request_with_retries(
+    api: &ElevenLabs,
+    text: &str,
+    api_key: &str,
+    voice_id: &str,
+    model: &str,
+    format: TtsFormat,
+) -> AnyhowResult<Bytes> {
+    let mut attempt = 1;
+    loop {
+        match eleven_labs_request(api, text, api_key, voice_id, model, format) {
+            Ok(bytes) => return Ok(bytes),
+            Err(RequestError::Transient(err)) if attempt < ELEVEN_LABS_ATTEMPTS => {
+                let delay = retry_delay(attempt, jitter());
+                warn!(
+                    "ElevenLabs request failed (attempt {attempt}/{ELEVEN_LABS_ATTEMPTS}), retrying in {delay:?}: {err}"
+                );
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            Err(RequestError::Quota { err, retry_after }) => {
+                start_cooldown(api_key, Instant::now(), retry_after.unwrap_or(QUOTA_COOLDOWN));
+                return Err(err);
+            }
+            Err(err) => return Err(err.into_inner()),
+        }
+    }
+}
+
+/// Backoff before retry number `attempt` (from 1): the base delay doubled for
+/// each earlier retry, plus `jitter` (a fraction in 0..1) of the base so that
+/// retries after a shared outage don't arrive in lockstep.
+fn retry_delay(attempt: u32, jitter: f64) -> Duration {
+    RETRY_BASE_DELAY * 2u32.pow(attempt - 1) + RETRY_BASE_DELAY.mul_f64(jitter)
+}
+
+/// A fraction in 0..1 from the clock's nanoseconds: random enough to spread
+/// retries, without a dependency on `rand`.
+fn jitter() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    f64::from(nanos) / 1e9
+}
+
+/// Pauses requests with `api_key` for `duration` from `now`, so every
+/// announcement in the meantime uses the built-in voice without first
+/// spending a request on a known failure.
+fn start_cooldown(api_key: &str, now: Instant, duration: Duration) {
+    warn!(
+        "ElevenLabs rejected the key or its quota is used up, using the built-in voice for the next {} min",
+        duration.as_secs().div_ceil(60)
+    );
+    *COOLDOWN.lock().unwrap_or_else(PoisonError::into_inner) = Some((api_key.to_string(), now + duration));
+}
+
+/// Whether requests with `api_key` are paused at `now`; logs (once) when a
+/// cooldown has run out.
+fn in_cooldown(api_key: &str, now: Instant) -> bool {
+    let mut cooldown = COOLDOWN.lock().unwrap_or_else(PoisonError::into_inner);
+    match &*cooldown {
+        Some((key, until)) if key == api_key => {
+            if now < *until {
+                return true;
+            }
+            info!("ElevenLabs cooldown over, trying the API again");
+            *cooldown = None;
+            false
+        }
+        _ => false,
+    }
+}
 
-fn eleven_labs_request(text: &str, api_key: &str) -> AnyhowResult<Bytes> {
-    let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()?;
-    let url = format!("https://api.elevenlabs.io/v1/text-to-speech/{VOICE_ID}?output_format=mp3_44100_128");
-    let response = client
+/// Whether a failed response means the key is unusable for now: a 401, or
+/// ElevenLabs' `quota_exceeded` error (sent with 401 or 429).
+fn quota_exceeded(status: StatusCode, body: &str) -> bool {
+    status == StatusCode::UNAUTHORIZED || body.contains("quota_exceeded")
+}
+
+/// Whether a failed response's `status` may succeed if repeated: rate
+/// limiting and server errors, not other client errors.
+fn retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn eleven_labs_request(
+    api: &ElevenLabs,
+    text: &str,
+    api_key: &str,
+    voice_id: &str,
+    model: &str,
+    format: TtsFormat,
+) -> Result<Bytes, RequestError> {
+    let voice_id = voice_id.trim();
+    if voice_id.is_empty() {
+        return Err(RequestError::Permanent(anyhow::anyhow!(
+            "eleven_labs_voice_id is empty"
+        )));
+    }
+    let url = format!(
+        "{}/v1/text-to-speech/{voice_id}?output_format={}",
+        api.base_url,
+        format.output_format()
+    );
+    let response = api
+        .client
         .post(&url)
         .header("xi-api-key", api_key)
         .header("Content-Type", "application/json")
         .json(&serde_json::json!({
             "text": text,
-            "model_id": "eleven_multilingual_v2"
+            "model_id": model
         }))
         .send()?;
 
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!("Unexpected status code: {}", response.status()));
+    let status = response.status();
+    if !status.is_success() {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+            .map(Duration::from_secs);
+        let body = response.text().unwrap_or_default();
+        let err = anyhow::anyhow!("Unexpected status code: {status}");
+        return Err(if quota_exceeded(status, &body) {
+            RequestError::Quota { err, retry_after }
+        } else if retryable(status) {
+            RequestError::Transient(err)
+        } else {
+            RequestError::Permanent(err)
+        });
     }
     Ok(response.bytes()?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::tts_friendly;
+    use super::{
+        ElevenLabs, RETRY_BASE_DELAY, StatusCode, TtsFormat, cache_key, eleven_labs_request, in_cooldown, pcm_samples,
+        quota_exceeded, retry_delay, retryable, save_to_disk, start_cooldown, tts_friendly, tts_volume,
+    };
+    use crate::config::Config;
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn cache_keys() {
+        // pinned: the key names files on disk, so it must not drift
+        assert_eq!(cache_key("", "", ""), 0x0832_8807_b4eb_6fed);
+        assert_eq!(cache_key("v", "m", "hello"), cache_key("v", "m", "hello"));
+        assert_ne!(cache_key("v", "m", "hello"), cache_key("w", "m", "hello"));
+        assert_ne!(cache_key("ab", "c", "x"), cache_key("a", "bc", "x"));
+    }
+
+    #[test]
+    fn disk_cache_evicts_oldest() {
+        let dir = std::env::temp_dir().join(format!("nextcall-tts-cache-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for name in ["a", "b", "c"] {
+            save_to_disk(&dir, &dir.join(name), &[0; 10], 25).unwrap();
+            // distinct mtimes, whatever the filesystem's resolution
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        // 30 bytes don't fit in 25: the oldest entry went
+        assert!(!dir.join("a").exists());
+        assert!(dir.join("b").exists() && dir.join("c").exists());
+        // an entry bigger than the bound is still kept (it's about to play)
+        save_to_disk(&dir, &dir.join("big"), &[0; 40], 25).unwrap();
+        assert!(dir.join("big").exists());
+        assert!(!dir.join("b").exists() && !dir.join("c").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn retry_backoff() {
+        assert_eq!(retry_delay(1, 0.0), RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(2, 0.0), RETRY_BASE_DELAY * 2);
+        assert_eq!(retry_delay(2, 0.5), RETRY_BASE_DELAY * 5 / 2);
+        assert!(retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(retryable(StatusCode::BAD_GATEWAY));
+        assert!(!retryable(StatusCode::UNAUTHORIZED));
+        assert!(!retryable(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    #[test]
+    fn quota_cooldown() {
+        assert!(quota_exceeded(StatusCode::UNAUTHORIZED, ""));
+        assert!(quota_exceeded(
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{"detail":{"status":"quota_exceeded"}}"#
+        ));
+        assert!(!quota_exceeded(StatusCode::TOO_MANY_REQUESTS, ""));
+
+        let now = Instant::now();
+        assert!(!in_cooldown("quota-test-key", now));
+        start_cooldown("quota-test-key", now, Duration::from_secs(60));
+        assert!(in_cooldown("quota-test-key", now + Duration::from_secs(59)));
+        // only the rejected key is paused
+        assert!(!in_cooldown("other-key", now));
+        assert!(!in_cooldown("quota-test-key", now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn base_url_points_requests_at_a_mock() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            // the JSON body follows the headers; read until it's all in
+            let complete = |request: &[u8]| {
+                let text = String::from_utf8_lossy(request);
+                let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                    return false;
+                };
+                let length = head.lines().find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length:")?
+                        .trim()
+                        .parse()
+                        .ok()
+                });
+                body.len() >= length.unwrap_or(0)
+            };
+            while !complete(&request) {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = b"canned mp3";
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(body).unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        let config = Config {
+            // a trailing slash doesn't double up in the path
+            eleven_labs_base_url: Some(format!("http://{addr}/")),
+            ..Config::default()
+        };
+        let api = ElevenLabs::new(&config).unwrap();
+        let bytes = eleven_labs_request(&api, "hello", "key", "voice", "model", TtsFormat::Mp3).unwrap();
+        assert_eq!(&bytes[..], b"canned mp3");
+        let request = server.join().unwrap();
+        assert!(
+            request.starts_with("POST /v1/text-to-speech/voice?output_format=mp3_44100_128 "),
+            "{request}"
+        );
+        assert!(request.to_ascii_lowercase().contains("xi-api-key: key"), "{request}");
+        assert!(request.contains(r#""text":"hello""#), "{request}");
+    }
+
+    #[test]
+    fn pcm_samples_decoded() {
+        let bytes = [0x00, 0x00, 0xff, 0x7f, 0x00, 0x80, 0x00, 0x40, 0x12];
+        // a trailing odd byte is dropped
+        assert_eq!(pcm_samples(&bytes), [0.0, 32767.0 / 32768.0, -1.0, 0.5]);
+    }
+
+    #[test]
+    fn tts_volume_clamped() {
+        let volume = |setting: Option<f32>| {
+            tts_volume(&Config {
+                tts_volume: setting,
+                ..Config::default()
+            })
+        };
+        assert!((volume(None) - 1.0).abs() < f32::EPSILON);
+        assert!((volume(Some(0.4)) - 0.4).abs() < f32::EPSILON);
+        assert!((volume(Some(50.0)) - 1.0).abs() < f32::EPSILON);
+        assert!(volume(Some(-1.0)).abs() < f32::EPSILON);
+        assert!((volume(Some(f32::NAN)) - 1.0).abs() < f32::EPSILON);
+    }
 
     /// One case per rewrite rule, using calendar-shaped titles.
     #[test]
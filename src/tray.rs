@@ -2,29 +2,168 @@
 //! `src/native/tray.m` (AppKit `NSStatusItem`).
 //!
 //! The countdown is plain text (the idle "..." renders as the logo glyph);
-//! the menu has a status line plus "Dismiss" (toggles to "Revert dismiss"),
-//! "View Log", "About nextcall" and "Quit". The tray owns the dismiss
-//! toggle; Rust polls [`dismissed_ts`] each tick.
+//! the menu has a status line, "Join: <next call>", an "Upcoming" agenda
+//! submenu, "Dismiss" (toggles to "Revert dismiss"), "Silence This Call" and
+//! "Pause Notifications" (checkable), "Refresh Now", "Preferences…" (saved
+//! to the config file, then [`Wake::Reload`]), "View Log", "About nextcall"
+//! (version, config and log paths, and the day's attendance) and "Quit". The
+//! tray owns the dismiss, silence and pause toggles; Rust polls
+//! [`dismissed_ts`], [`silenced_ts`] and [`paused`] each tick. "Refresh Now"
+//! wakes the background loop with [`Wake::Refresh`].
 
-use std::ffi::{CString, c_char};
+use crate::config::{self, Preferences};
+use crate::ical::{self, NextEvent};
+use crate::logic;
+use crate::wake::{self, Wake};
+use crate::{browser, history};
+use chrono::Utc;
+use std::ffi::{CStr, CString, c_char};
+use std::path::Path;
+use std::ptr;
+use tracing::{error, info};
 
 unsafe extern "C" {
-    fn tray_run();
+    fn tray_run(
+        on_refresh: extern "C" fn(),
+        on_save_preferences: extern "C" fn(
+            ical_url: *const c_char,
+            eleven_labs_key: *const c_char,
+            builtin_voice: *const c_char,
+            notify_before_minutes: *const c_char,
+            force: bool,
+            error: *mut c_char,
+            error_len: usize,
+        ) -> bool,
+        on_open_link: extern "C" fn(url: *const c_char) -> bool,
+        on_join: extern "C" fn(start_ts: i64, url: *const c_char),
+    );
     fn tray_set_title(title: *const c_char);
     fn tray_set_status(status: *const c_char);
     fn tray_set_log_path(path: *const c_char);
+    fn tray_set_about(version: *const c_char, config_path: *const c_char, config_search: *const c_char);
+    fn tray_set_attendance(attendance: *const c_char);
+    fn tray_set_preferences(
+        ical_url: *const c_char,
+        eleven_labs_key: *const c_char,
+        builtin_voice: *const c_char,
+        notify_before_minutes: *const c_char,
+    );
     fn tray_set_dismiss_target(start_ts: i64);
     fn tray_dismissed_ts() -> i64;
+    fn tray_silenced_ts() -> i64;
+    fn tray_paused() -> bool;
     fn tray_set_warning(warning: bool);
+    fn tray_set_all_clear(all_clear: bool);
+    fn tray_set_progress(progress: f64);
+    fn tray_set_tooltip(tooltip: *const c_char);
+    fn tray_set_urgency(urgency: i32);
+    fn tray_set_join(summary: *const c_char, url: *const c_char, fallback_url: *const c_char);
+    fn tray_clear_upcoming();
+    fn tray_add_upcoming(label: *const c_char, url: *const c_char, fallback_url: *const c_char);
+}
+
+/// How close the next call is, shown as the countdown's colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Urgency {
+    /// The default menu bar text colour.
+    Normal = 0,
+    /// Starting within [`logic::SOON_MINUTES`](crate::logic::SOON_MINUTES): orange.
+    Soon = 1,
+    /// Started and not yet joined: red.
+    Started = 2,
 }
 
 /// Creates the status item and runs the AppKit event loop. Never returns:
 /// "Quit" terminates the process. Must be called on the main thread.
 pub fn run() -> ! {
-    unsafe { tray_run() }
+    unsafe { tray_run(on_refresh, on_save_preferences, browser::on_open_link, history::on_join) }
     unreachable!("tray_run only returns when the app is terminating")
 }
 
+/// "Refresh Now" callback, invoked on the main thread.
+extern "C" fn on_refresh() {
+    wake::send(Wake::Refresh);
+}
+
+/// "Preferences…" callback, invoked on a background queue with the dialog's
+/// fields (empty = unset): unless `force`d, checks a changed calendar URL
+/// serves a calendar, then saves them to the config file and has the
+/// background loop reload. On failure returns false with the reason written
+/// (NUL-terminated, truncated) into the `error` buffer.
+extern "C" fn on_save_preferences(
+    ical_url: *const c_char,
+    eleven_labs_key: *const c_char,
+    builtin_voice: *const c_char,
+    notify_before_minutes: *const c_char,
+    force: bool,
+    error: *mut c_char,
+    error_len: usize,
+) -> bool {
+    let field = |value: *const c_char| {
+        let value = unsafe { CStr::from_ptr(value) }.to_string_lossy().trim().to_string();
+        (!value.is_empty()).then_some(value)
+    };
+    let result = save_preferences(
+        field(ical_url).unwrap_or_default(),
+        field(eleven_labs_key),
+        field(builtin_voice),
+        field(notify_before_minutes),
+        force,
+    );
+    match result {
+        Ok(()) => {
+            wake::send(Wake::Reload);
+            true
+        }
+        Err(message) => {
+            error!("preferences not saved: {message}");
+            // truncate on a char boundary so the ObjC side gets valid UTF-8
+            let mut len = message.len().min(error_len.saturating_sub(1));
+            while !message.is_char_boundary(len) {
+                len -= 1;
+            }
+            unsafe {
+                ptr::copy_nonoverlapping(message.as_ptr().cast::<c_char>(), error, len);
+                *error.add(len) = 0;
+            }
+            false
+        }
+    }
+}
+
+/// Validates and saves the Preferences dialog's fields; the error is the
+/// message shown in the dialog.
+fn save_preferences(
+    ical_url: String,
+    eleven_labs_key: Option<String>,
+    builtin_voice: Option<String>,
+    notify_before_minutes: Option<String>,
+    force: bool,
+) -> Result<(), String> {
+    if ical_url.is_empty() {
+        return Err("a calendar URL is required".to_string());
+    }
+    let notify_before_minutes = notify_before_minutes
+        .map(|n| n.parse::<u32>())
+        .transpose()
+        .map_err(|_| "minutes before must be a whole number".to_string())?;
+    let current = config::get_config().ok().flatten().unwrap_or_default();
+    // only a changed URL is fetched, bounded by the current HTTP timeouts
+    if !force && ical_url != current.ical_url {
+        ical::validate_url(&ical_url, &current).map_err(|err| format!("calendar URL didn't load: {err}"))?;
+    }
+    let preferences = Preferences {
+        ical_url,
+        eleven_labs_key,
+        builtin_voice,
+        notify_before_minutes,
+    };
+    let path = config::save(&preferences).map_err(|err| format!("{err:#}"))?;
+    info!("saved preferences to {}", path.display());
+    Ok(())
+}
+
 /// Tells the tray where this run's log file lives, so the menu's "View Log"
 /// item can open it. Call once at startup; until then the item does nothing.
 /// Thread-safe like [`set_title`].
@@ -33,6 +172,61 @@ pub fn set_log_path(path: &str) {
     unsafe { tray_set_log_path(path.as_ptr()) }
 }
 
+/// Tells the tray what "About Nextcall" shows besides the log path: this
+/// build's version, the config file in use (made absolute, as the one in the
+/// working directory is found by a relative path) and the order in which
+/// config files are looked for. Call at startup and after a config reload.
+/// Thread-safe like [`set_title`].
+pub fn set_about(config_path: Option<&Path>) {
+    let version = CString::new(env!("CARGO_PKG_VERSION")).unwrap_or_default();
+    let config_path = config_path
+        .map(|p| std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf()))
+        .and_then(|p| CString::new(p.to_string_lossy().into_owned()).ok());
+    let search = config::search_paths()
+        .unwrap_or_default()
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let search = CString::new(format!("${}, then {search}", config::CONFIG_ENV)).unwrap_or_default();
+    unsafe {
+        tray_set_about(
+            version.as_ptr(),
+            config_path.as_ref().map_or(ptr::null(), |p| p.as_ptr()),
+            search.as_ptr(),
+        )
+    }
+}
+
+/// Sets the attendance line "About Nextcall" shows (see
+/// [`history::attendance`]). Call when it changes. Thread-safe like
+/// [`set_title`].
+pub fn set_attendance(attendance: &str) {
+    let Ok(attendance) = CString::new(attendance) else {
+        return;
+    };
+    unsafe { tray_set_attendance(attendance.as_ptr()) }
+}
+
+/// Tells the tray the current preferences, so the "Preferences…" dialog opens
+/// with them for editing. Call at startup and after a config reload.
+/// Thread-safe like [`set_title`].
+pub fn set_preferences(preferences: &Preferences) {
+    let cstring = |value: Option<&str>| CString::new(value.unwrap_or_default()).unwrap_or_default();
+    let ical_url = cstring(Some(&preferences.ical_url));
+    let eleven_labs_key = cstring(preferences.eleven_labs_key.as_deref());
+    let builtin_voice = cstring(preferences.builtin_voice.as_deref());
+    let notify_before_minutes = cstring(preferences.notify_before_minutes.map(|n| n.to_string()).as_deref());
+    unsafe {
+        tray_set_preferences(
+            ical_url.as_ptr(),
+            eleven_labs_key.as_ptr(),
+            builtin_voice.as_ptr(),
+            notify_before_minutes.as_ptr(),
+        )
+    }
+}
+
 /// Updates the status line at the top of the tray menu (e.g. "Next: standup
 /// at 14:00"). Thread-safe like [`set_title`].
 pub fn set_status(status: &str) {
@@ -40,6 +234,13 @@ pub fn set_status(status: &str) {
     unsafe { tray_set_status(status.as_ptr()) }
 }
 
+/// Sets the hover text of the menu bar item (e.g. "standup at 10:00 — in
+/// 12m"). Thread-safe like [`set_title`].
+pub fn set_tooltip(tooltip: &str) {
+    let Ok(tooltip) = CString::new(tooltip) else { return };
+    unsafe { tray_set_tooltip(tooltip.as_ptr()) }
+}
+
 /// Arms the menu's "Dismiss" item with the current call's start unix time
 /// (0 disables it: no upcoming call), also expiring a recorded dismissal that
 /// no longer refers to that call. Call every tick so a click always targets
@@ -48,6 +249,12 @@ pub fn set_dismiss_target(start_ts: i64) {
     unsafe { tray_set_dismiss_target(start_ts) }
 }
 
+/// Whether "Pause Notifications" is checked: every call's alerts are muted
+/// until it is unchecked. Session-only; thread-safe.
+pub fn paused() -> bool {
+    unsafe { tray_paused() }
+}
+
 /// The start unix time of the call the user dismissed via the menu, or
 /// `None`. The tray owns the dismiss toggle; the caller must match this
 /// against the *current* next call — a stale value (the call changed while we
@@ -59,6 +266,75 @@ pub fn dismissed_ts() -> Option<i64> {
     }
 }
 
+/// The start unix time of the call silenced via "Silence This Call" (alerts
+/// still show, without sound or speech), or `None`. Like [`dismissed_ts`], it
+/// must be matched against the current next call. Thread-safe.
+pub fn silenced_ts() -> Option<i64> {
+    match unsafe { tray_silenced_ts() } {
+        0 => None,
+        ts => Some(ts),
+    }
+}
+
+/// Arms the menu's "Join: <summary>" item with the next call, opening its
+/// native-app link if one is installed, else its web link. `None` disables
+/// the item. Call every tick. Thread-safe like [`set_title`].
+pub fn set_join(event: Option<&NextEvent>) {
+    let cstring = |s: &str| CString::new(s).ok();
+    let Some(event) = event else {
+        unsafe { tray_set_join(ptr::null(), ptr::null(), ptr::null()) }
+        return;
+    };
+    let summary = cstring(&event.summary).unwrap_or_default();
+    let url = cstring(event.app_link.as_deref().unwrap_or(&event.video_link));
+    let fallback_url = event.app_link.as_ref().and_then(|_| cstring(&event.video_link));
+    unsafe {
+        tray_set_join(
+            summary.as_ptr(),
+            url.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            fallback_url.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+        )
+    }
+}
+
+/// Replaces the "Upcoming" submenu with `events`, one item each labelled with
+/// the local start time (and weekday, if not today) and summary, opening the
+/// call's link like "Join". Empty disables the submenu. Thread-safe like
+/// [`set_title`].
+pub fn set_upcoming(events: &[NextEvent]) {
+    unsafe { tray_clear_upcoming() }
+    let today = ical::local_date(Utc::now());
+    for event in events {
+        let time = logic::local_time_string(event.start_time);
+        let label = if ical::local_date(event.start_time) == today {
+            format!("{time}  {}", event.summary)
+        } else {
+            format!(
+                "{} {time}  {}",
+                logic::local_day_string(event.start_time),
+                event.summary
+            )
+        };
+        let (Ok(label), Ok(url)) = (
+            CString::new(label),
+            CString::new(event.app_link.as_deref().unwrap_or(&event.video_link)),
+        ) else {
+            continue;
+        };
+        let fallback_url = event
+            .app_link
+            .as_ref()
+            .and_then(|_| CString::new(event.video_link.as_str()).ok());
+        unsafe {
+            tray_add_upcoming(
+                label.as_ptr(),
+                url.as_ptr(),
+                fallback_url.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            )
+        }
+    }
+}
+
 /// Shows/hides the warning triangle next to the countdown/logo, indicating
 /// calendar fetches are failing (details go to the log, not a notification).
 /// Call every fetch cycle with the current outcome so the icon self-clears
@@ -67,6 +343,24 @@ pub fn set_warning(warning: bool) {
     unsafe { tray_set_warning(warning) }
 }
 
+/// Shows a checkmark instead of the idle logo while the calendar has no
+/// upcoming call at all, telling "nothing scheduled" apart from "next call
+/// hours away". Call every tick. Thread-safe like [`set_title`].
+pub fn set_all_clear(all_clear: bool) {
+    unsafe { tray_set_all_clear(all_clear) }
+}
+
+/// Draws a ring beside the countdown, filled by `progress` (0.0 to 1.0);
+/// `None` removes it. Call every tick. Thread-safe like [`set_title`].
+pub fn set_progress(progress: Option<f64>) {
+    unsafe { tray_set_progress(progress.map_or(-1.0, |p| p.clamp(0.0, 1.0))) }
+}
+
+/// Colours the countdown text by [`Urgency`]. Thread-safe like [`set_title`].
+pub fn set_urgency(urgency: Urgency) {
+    unsafe { tray_set_urgency(urgency as i32) }
+}
+
 /// Sets the menu bar text (e.g. "5", "-2", "..."). The tray renders the idle
 /// "..." as the logo glyph rather than literal text. Thread-safe: the update
 /// is dispatched to the main queue, and is queued if called before [`run`].
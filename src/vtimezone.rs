@@ -0,0 +1,251 @@
+//! Time zones defined inline by a feed's VTIMEZONE components, for TZIDs
+//! chrono-tz doesn't know (Outlook's "Customized Time Zone" and friends).
+//!
+//! Only what calendar feeds use in practice is supported: each STANDARD /
+//! DAYLIGHT observance's `TZOFFSETTO`, with an optional yearly
+//! `BYMONTH`/`BYDAY` rule (e.g. "last Sunday of October") for its onset.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeDelta, Utc, Weekday};
+use ical::parser::ical::component::IcalTimeZone;
+use std::collections::HashMap;
+
+/// A feed's custom time zones by TZID.
+pub type Timezones = HashMap<String, VTimezone>;
+
+/// One VTIMEZONE: the observances whose onsets partition local time.
+#[derive(Debug)]
+pub struct VTimezone {
+    observances: Vec<Observance>,
+}
+
+/// A STANDARD or DAYLIGHT block: the offset in force from its onset on.
+#[derive(Debug)]
+struct Observance {
+    /// `TZOFFSETTO`: local time minus UTC.
+    offset: TimeDelta,
+    /// `DTSTART`: the first onset, in the local time before it.
+    start: NaiveDateTime,
+    /// Yearly repetition of the onset; `None` for a one-off transition.
+    rule: Option<YearlyRule>,
+}
+
+/// `FREQ=YEARLY;BYMONTH=m;BYDAY=nWD`: the `week`th (negative counts from the
+/// end) `weekday` of `month`, at the observance's DTSTART time of day.
+#[derive(Debug)]
+struct YearlyRule {
+    month: u32,
+    week: i8,
+    weekday: Weekday,
+}
+
+/// Parses every VTIMEZONE with a TZID and at least one usable observance.
+pub fn parse_timezones(zones: &[IcalTimeZone]) -> Timezones {
+    zones
+        .iter()
+        .filter_map(|zone| {
+            let tzid = property(&zone.properties, "TZID")?;
+            let observances: Vec<Observance> = zone
+                .transitions
+                .iter()
+                .filter_map(|t| {
+                    Some(Observance {
+                        offset: parse_offset(&property(&t.properties, "TZOFFSETTO")?)?,
+                        start: NaiveDateTime::parse_from_str(&property(&t.properties, "DTSTART")?, "%Y%m%dT%H%M%S")
+                            .ok()?,
+                        rule: property(&t.properties, "RRULE").and_then(|r| parse_rule(&r)),
+                    })
+                })
+                .collect();
+            (!observances.is_empty()).then_some((tzid, VTimezone { observances }))
+        })
+        .collect()
+}
+
+impl VTimezone {
+    /// The UTC offset in force at the wall-clock time `local`: that of the
+    /// observance with the latest onset at or before it (the earliest-starting
+    /// one before any onset at all).
+    pub fn offset_at(&self, local: NaiveDateTime) -> TimeDelta {
+        self.observances
+            .iter()
+            .filter_map(|o| Some((o.last_onset(local)?, o.offset)))
+            .max_by_key(|(onset, _)| *onset)
+            .or_else(|| {
+                self.observances
+                    .iter()
+                    .map(|o| (o.start, o.offset))
+                    .min_by_key(|(s, _)| *s)
+            })
+            .map_or(TimeDelta::zero(), |(_, offset)| offset)
+    }
+
+    /// Converts the wall-clock time `local` in this zone to UTC.
+    pub fn to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        (local - self.offset_at(local)).and_utc()
+    }
+}
+
+impl Observance {
+    /// The latest onset at or before `local`, if any.
+    fn last_onset(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        if local < self.start {
+            return None;
+        }
+        let Some(rule) = &self.rule else {
+            return Some(self.start);
+        };
+        [local.year(), local.year() - 1]
+            .into_iter()
+            .filter_map(|year| Some(rule.date(year)?.and_time(self.start.time())))
+            .find(|onset| *onset <= local)
+            .map(|onset| onset.max(self.start))
+    }
+}
+
+impl YearlyRule {
+    /// This rule's onset date in `year`, if the month has such a weekday.
+    fn date(&self, year: i32) -> Option<NaiveDate> {
+        if self.week > 0 {
+            return NaiveDate::from_weekday_of_month_opt(year, self.month, self.weekday, self.week as u8);
+        }
+        // counted from the end: the last matches first
+        let mut matches: Vec<NaiveDate> = (1..=5)
+            .filter_map(|n| NaiveDate::from_weekday_of_month_opt(year, self.month, self.weekday, n))
+            .collect();
+        matches.reverse();
+        matches
+            .get(usize::from(self.week.unsigned_abs()).checked_sub(1)?)
+            .copied()
+    }
+}
+
+/// The value of the first property called `name`.
+fn property(properties: &[ical::property::Property], name: &str) -> Option<String> {
+    properties.iter().find(|p| p.name == name)?.value.clone()
+}
+
+/// Parses a UTC offset such as `+0100`, `-0500` or `+053000`.
+fn parse_offset(value: &str) -> Option<TimeDelta> {
+    let (sign, digits) = match value.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if !matches!(digits.len(), 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let field = |i: usize| digits.get(i..i + 2).map_or(Ok(0), str::parse::<i64>);
+    let seconds = field(0).ok()? * 3600 + field(2).ok()? * 60 + field(4).ok()?;
+    Some(TimeDelta::seconds(sign * seconds))
+}
+
+/// Parses a yearly `BYMONTH`/`BYDAY` onset rule; anything else is `None`.
+fn parse_rule(value: &str) -> Option<YearlyRule> {
+    let mut freq = None;
+    let mut month = None;
+    let mut by_day = None;
+    for part in value.split(';') {
+        match part.split_once('=')? {
+            ("FREQ", f) => freq = Some(f),
+            ("BYMONTH", m) => month = m.parse().ok(),
+            ("BYDAY", d) => by_day = Some(d),
+            _ => {}
+        }
+    }
+    if freq != Some("YEARLY") {
+        return None;
+    }
+    let by_day = by_day?;
+    let (week, day) = by_day.split_at_checked(by_day.len().checked_sub(2)?)?;
+    let weekday = match day {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(YearlyRule {
+        month: month?,
+        week: week.trim_start_matches('+').parse().ok().filter(|w: &i8| *w != 0)?,
+        weekday,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ical::IcalParser;
+    use std::io::BufReader;
+
+    /// Outlook's rendering of Central European Time under a custom name.
+    const CUSTOM_CET: &str = "BEGIN:VTIMEZONE\nTZID:Customized Time Zone\n\
+        BEGIN:STANDARD\nDTSTART:16010101T030000\nTZOFFSETFROM:+0200\nTZOFFSETTO:+0100\n\
+        RRULE:FREQ=YEARLY;INTERVAL=1;BYDAY=-1SU;BYMONTH=10\nEND:STANDARD\n\
+        BEGIN:DAYLIGHT\nDTSTART:16010101T020000\nTZOFFSETFROM:+0100\nTZOFFSETTO:+0200\n\
+        RRULE:FREQ=YEARLY;INTERVAL=1;BYDAY=-1SU;BYMONTH=3\nEND:DAYLIGHT\nEND:VTIMEZONE\n";
+
+    fn zones(vtimezones: &str) -> Timezones {
+        let content = format!("BEGIN:VCALENDAR\nVERSION:2.0\n{vtimezones}END:VCALENDAR\n");
+        let calendar = IcalParser::new(BufReader::new(content.as_bytes()))
+            .next()
+            .unwrap()
+            .unwrap();
+        parse_timezones(&calendar.timezones)
+    }
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, mo, d)
+            .unwrap()
+            .and_hms_opt(h, mi, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn yearly_rules_switch_offsets() {
+        let zones = zones(CUSTOM_CET);
+        let zone = &zones["Customized Time Zone"];
+        let hours = |t| zone.offset_at(t).num_hours();
+        assert_eq!(hours(local(2026, 1, 15, 12, 0)), 1);
+        assert_eq!(hours(local(2026, 7, 9, 11, 0)), 2);
+        // 2026: summer time from Sunday 29 March 02:00 to Sunday 25 October 03:00
+        assert_eq!(hours(local(2026, 3, 29, 1, 59)), 1);
+        assert_eq!(hours(local(2026, 3, 29, 2, 0)), 2);
+        assert_eq!(hours(local(2026, 10, 25, 2, 59)), 2);
+        assert_eq!(hours(local(2026, 10, 25, 3, 0)), 1);
+        assert_eq!(zone.to_utc(local(2026, 7, 9, 11, 0)), local(2026, 7, 9, 9, 0).and_utc());
+    }
+
+    #[test]
+    fn southern_hemisphere_and_fixed_zones() {
+        let zones = zones(
+            "BEGIN:VTIMEZONE\nTZID:Sydney custom\n\
+             BEGIN:STANDARD\nDTSTART:16010101T030000\nTZOFFSETTO:+1000\nRRULE:FREQ=YEARLY;BYDAY=1SU;BYMONTH=4\nEND:STANDARD\n\
+             BEGIN:DAYLIGHT\nDTSTART:16010101T020000\nTZOFFSETTO:+1100\nRRULE:FREQ=YEARLY;BYDAY=1SU;BYMONTH=10\nEND:DAYLIGHT\n\
+             END:VTIMEZONE\n\
+             BEGIN:VTIMEZONE\nTZID:India custom\n\
+             BEGIN:STANDARD\nDTSTART:16010101T000000\nTZOFFSETTO:+0530\nEND:STANDARD\nEND:VTIMEZONE\n",
+        );
+        let sydney = &zones["Sydney custom"];
+        assert_eq!(sydney.offset_at(local(2026, 1, 15, 12, 0)).num_hours(), 11);
+        assert_eq!(sydney.offset_at(local(2026, 7, 9, 12, 0)).num_hours(), 10);
+        assert_eq!(
+            zones["India custom"].offset_at(local(2026, 7, 9, 12, 0)).num_minutes(),
+            330
+        );
+    }
+
+    #[test]
+    fn offsets_and_rules_parse() {
+        assert_eq!(parse_offset("-0500"), Some(TimeDelta::hours(-5)));
+        assert_eq!(parse_offset("+053000"), Some(TimeDelta::minutes(330)));
+        assert_eq!(parse_offset("0100"), None);
+        assert_eq!(parse_offset("+1"), None);
+        let rule = parse_rule("FREQ=YEARLY;BYDAY=2SU;BYMONTH=3").unwrap();
+        assert_eq!(rule.date(2026), NaiveDate::from_ymd_opt(2026, 3, 8));
+        assert!(parse_rule("FREQ=MONTHLY;BYDAY=2SU;BYMONTH=3").is_none());
+        assert!(parse_rule("FREQ=YEARLY;BYDAY=SU;BYMONTH=3").is_none());
+    }
+}
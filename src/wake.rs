@@ -0,0 +1,38 @@
+//! Early wake-ups for the background loop, which otherwise sleeps until its
+//! next scheduled tick. Event sources on other threads (e.g. the network
+//! monitor's callback) [`send`] a [`Wake`]; the loop waits on the receiver
+//! from [`channel`] with a timeout instead of a plain sleep.
+
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Why the background loop was woken early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wake {
+    /// Connectivity returned after being lost: refetch if the last fetch failed.
+    NetworkUp,
+    /// The user asked for a refresh from the tray menu: refetch now.
+    Refresh,
+    /// The config file changed (edited on disk, see [`crate::config::watch`],
+    /// or saved from the tray): reload it and refetch.
+    Reload,
+}
+
+/// The loop's sender; set once by [`channel`].
+static SENDER: OnceLock<Sender<Wake>> = OnceLock::new();
+
+/// Creates the wake channel, returning the receiver for the background loop.
+/// Call once, before starting any wake source; later calls return a receiver
+/// that never gets anything.
+pub fn channel() -> Receiver<Wake> {
+    let (sender, receiver) = mpsc::channel();
+    let _ = SENDER.set(sender);
+    receiver
+}
+
+/// Wakes the background loop. Thread-safe; a no-op before [`channel`].
+pub fn send(wake: Wake) {
+    if let Some(sender) = SENDER.get() {
+        let _ = sender.send(wake);
+    }
+}